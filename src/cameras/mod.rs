@@ -1,3 +1,5 @@
 mod perspective;
+mod stereo;
 
-pub use self::perspective::PerspectiveCamera;
\ No newline at end of file
+pub use self::perspective::PerspectiveCamera;
+pub use self::stereo::{Eye, StereoCamera, StereoLayout, StereoRig};
@@ -16,6 +16,10 @@ pub struct PerspectiveCamera<'a> {
     film: &'a mut Film,
     shutter_open: Float,
     shutter_close: Float,
+    projection: PerspectiveProjection,
+}
+
+pub struct PerspectiveProjection {
     camera_to_world: Transform,
     camera_to_screen: Transform,
     raster_to_camera: Transform,
@@ -36,11 +40,28 @@ impl<'a> PerspectiveCamera<'a> {
                    focal_distance: Float,
                    fov: Float,
                    film: &'b mut Film) -> PerspectiveCamera<'b> {
+        let projection = PerspectiveProjection::new(camera_to_world, screen_window, film.resolution(), lens_radius, focal_distance, fov);
+        PerspectiveCamera {
+            film,
+            shutter_open,
+            shutter_close,
+            projection,
+        }
+    }
+}
+
+impl PerspectiveProjection {
+    pub fn new(camera_to_world: &Transform,
+               screen_window: [Float; 4],
+               resolution: (u32, u32),
+               lens_radius: Float,
+               focal_distance: Float,
+               fov: Float) -> PerspectiveProjection {
         // Compute projective camera transformations
         let camera_to_screen = perspective(fov, 1e-2, 1000.0);
 
         // Compute projective camera screen transformations
-        let (res_x, res_y) = film.resolution();
+        let (res_x, res_y) = resolution;
         let screen_to_raster =
             &scale(res_x as Float, res_y as Float, 1.0) *
                 &scale(1.0 / (screen_window[1] - screen_window[0]), 1.0 / (screen_window[2] - screen_window[3]), 1.0) *
@@ -50,10 +71,7 @@ impl<'a> PerspectiveCamera<'a> {
 
         let dx_camera = &raster_to_camera.transform_point(Point3f::new(1.0, 0.0, 0.0)) - raster_to_camera.transform_point(Point3f::new(0.0, 0.0, 0.0));
         let dy_camera = &raster_to_camera.transform_point(Point3f::new(0.0, 1.0, 0.0)) - raster_to_camera.transform_point(Point3f::new(0.0, 0.0, 0.0));
-        PerspectiveCamera {
-            film,
-            shutter_open,
-            shutter_close,
+        PerspectiveProjection {
             camera_to_world: camera_to_world.clone(),
             camera_to_screen,
             raster_to_camera,
@@ -65,10 +83,8 @@ impl<'a> PerspectiveCamera<'a> {
             dy_camera,
         }
     }
-}
 
-impl<'a> Camera for PerspectiveCamera<'a> {
-    fn generate_ray(&self, sample: &CameraSample) -> (Ray, Float) {
+    pub fn generate_ray(&self, sample: &CameraSample) -> (Ray, Float) {
         // Generate raster and camera samples
         let pras = Point3f::new(sample.image_x, sample.image_y, 0.0);
         let pcamera = self.raster_to_camera.transform_point(pras);
@@ -94,7 +110,7 @@ impl<'a> Camera for PerspectiveCamera<'a> {
         return (self.camera_to_world.transform_ray(&ray), 1.0);
     }
 
-    fn generate_ray_differential(&self, sample: &CameraSample) -> (RayDifferential, Float) {
+    pub fn generate_ray_differential(&self, sample: &CameraSample) -> (RayDifferential, Float) {
         // Generate raster and camera samples
         let pras = Point3f::new(sample.image_x, sample.image_y, 0.0);
         let pcamera = self.raster_to_camera.transform_point(pras);
@@ -156,6 +172,16 @@ impl<'a> Camera for PerspectiveCamera<'a> {
 
         return (rd, 1.0);
     }
+}
+
+impl<'a> Camera for PerspectiveCamera<'a> {
+    fn generate_ray(&self, sample: &CameraSample) -> (Ray, Float) {
+        self.projection.generate_ray(sample)
+    }
+
+    fn generate_ray_differential(&self, sample: &CameraSample) -> (RayDifferential, Float) {
+        self.projection.generate_ray_differential(sample)
+    }
 
     fn get_film(&mut self) -> &mut Film {
         self.film
@@ -0,0 +1,225 @@
+use core::camera::Camera;
+use core::film::Film;
+use core::geometry::{Point3f, Ray, RayDifferential, RayDifferentials};
+use core::math::{lerp, radians};
+use core::sampler::CameraSample;
+use core::transform::{Transform, translate};
+use core::types::{Float, INFINITY, PI};
+use cgmath::vec3;
+use super::perspective::{PerspectiveCamera, PerspectiveProjection};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Eye {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StereoLayout {
+    SideBySide,
+    OverUnder,
+}
+
+// Placement and framing shared by both eyes. _screen_window_ and _fov_ describe the view of the
+// rig's center, and are ignored by omni-directional cameras.
+pub struct StereoRig {
+    camera_to_world: Transform,
+    screen_window: [Float; 4],
+    fov: Float,
+    interaxial: Float,
+    convergence_distance: Float,
+    layout: StereoLayout,
+}
+
+impl StereoRig {
+    // A _convergence_distance_ of _INFINITY_ gives a parallel rig
+    pub fn new(camera_to_world: &Transform,
+               screen_window: [Float; 4],
+               fov: Float,
+               interaxial: Float,
+               convergence_distance: Float,
+               layout: StereoLayout) -> StereoRig {
+        StereoRig {
+            camera_to_world: camera_to_world.clone(),
+            screen_window,
+            fov,
+            interaxial,
+            convergence_distance,
+            layout,
+        }
+    }
+
+    fn eye_offset(&self, eye: Eye) -> Float {
+        match eye {
+            Eye::Left => -0.5 * self.interaxial,
+            Eye::Right => 0.5 * self.interaxial,
+        }
+    }
+
+    pub fn eye_to_world(&self, eye: Eye) -> Transform {
+        &self.camera_to_world * &translate(&vec3(self.eye_offset(eye), 0.0, 0.0))
+    }
+
+    pub fn eye_screen_window(&self, eye: Eye) -> [Float; 4] {
+        // Shift the screen window so that the eyes' frusta meet at the convergence plane
+        let inv_tan_ang = 1.0 / (radians(self.fov) / 2.0).tan();
+        let shift = -self.eye_offset(eye) * inv_tan_ang / self.convergence_distance;
+        let w = self.screen_window;
+        [w[0] + shift, w[1] + shift, w[2], w[3]]
+    }
+
+    // Camera for rendering a single eye into a film of its own
+    pub fn eye_camera<'b>(&self,
+                          eye: Eye,
+                          shutter_open: Float,
+                          shutter_close: Float,
+                          lens_radius: Float,
+                          focal_distance: Float,
+                          film: &'b mut Film) -> PerspectiveCamera<'b> {
+        PerspectiveCamera::new(&self.eye_to_world(eye), self.eye_screen_window(eye),
+                               shutter_open, shutter_close, lens_radius, focal_distance, self.fov, film)
+    }
+}
+
+enum EyeProjection {
+    Perspective(Box<[PerspectiveProjection; 2]>),
+    OmniDirectional { camera_to_world: Transform, interaxial: Float },
+}
+
+pub struct StereoCamera<'a> {
+    film: &'a mut Film,
+    shutter_open: Float,
+    shutter_close: Float,
+    layout: StereoLayout,
+    eye_resolution: (u32, u32),
+    projection: EyeProjection,
+}
+
+impl<'a> StereoCamera<'a> {
+    pub fn new<'b>(rig: &StereoRig,
+                   shutter_open: Float,
+                   shutter_close: Float,
+                   lens_radius: Float,
+                   focal_distance: Float,
+                   film: &'b mut Film) -> StereoCamera<'b> {
+        let eye_resolution = eye_resolution(rig.layout, film.resolution());
+        let projection = |eye| {
+            PerspectiveProjection::new(&rig.eye_to_world(eye), rig.eye_screen_window(eye),
+                                       eye_resolution, lens_radius, focal_distance, rig.fov)
+        };
+
+        StereoCamera {
+            film,
+            shutter_open,
+            shutter_close,
+            layout: rig.layout,
+            eye_resolution,
+            projection: EyeProjection::Perspective(Box::new([projection(Eye::Left), projection(Eye::Right)])),
+        }
+    }
+
+    pub fn new_omnidirectional<'b>(rig: &StereoRig,
+                                   shutter_open: Float,
+                                   shutter_close: Float,
+                                   film: &'b mut Film) -> StereoCamera<'b> {
+        let eye_resolution = eye_resolution(rig.layout, film.resolution());
+        StereoCamera {
+            film,
+            shutter_open,
+            shutter_close,
+            layout: rig.layout,
+            eye_resolution,
+            projection: EyeProjection::OmniDirectional {
+                camera_to_world: rig.camera_to_world.clone(),
+                interaxial: rig.interaxial,
+            },
+        }
+    }
+
+    // Map a film sample to the eye it belongs to and that eye's raster space, with its time
+    // within the shutter interval
+    fn eye_sample(&self, sample: &CameraSample) -> (Eye, CameraSample) {
+        let (width, height) = (self.eye_resolution.0 as Float, self.eye_resolution.1 as Float);
+        let sample = &CameraSample { time: lerp(sample.time, self.shutter_open, self.shutter_close), ..*sample };
+        match self.layout {
+            StereoLayout::SideBySide if sample.image_x >= width =>
+                (Eye::Right, CameraSample { image_x: sample.image_x - width, ..*sample }),
+            StereoLayout::OverUnder if sample.image_y >= height =>
+                (Eye::Right, CameraSample { image_y: sample.image_y - height, ..*sample }),
+            _ =>
+                (Eye::Left, CameraSample { ..*sample }),
+        }
+    }
+
+    fn omnidirectional_ray(&self, camera_to_world: &Transform, interaxial: Float, eye: Eye, sample: &CameraSample) -> Ray {
+        // Compute equirectangular direction for raster position
+        let theta = PI * sample.image_y / (self.eye_resolution.1 as Float);
+        let phi = 2.0 * PI * (sample.image_x / (self.eye_resolution.0 as Float) - 0.5);
+        let dir = vec3(theta.sin() * phi.sin(), theta.cos(), theta.sin() * phi.cos());
+
+        // Offset ray origin tangentially to the viewing circle
+        let offset = match eye {
+            Eye::Left => -0.5 * interaxial,
+            Eye::Right => 0.5 * interaxial,
+        };
+        let origin = Point3f::new(offset * phi.cos(), 0.0, -offset * phi.sin());
+
+        camera_to_world.transform_ray(&Ray::new(origin, dir, 0.0, INFINITY, sample.time))
+    }
+}
+
+fn eye_resolution(layout: StereoLayout, (res_x, res_y): (u32, u32)) -> (u32, u32) {
+    match layout {
+        StereoLayout::SideBySide => (res_x / 2, res_y),
+        StereoLayout::OverUnder => (res_x, res_y / 2),
+    }
+}
+
+fn eye_index(eye: Eye) -> usize {
+    match eye {
+        Eye::Left => 0,
+        Eye::Right => 1,
+    }
+}
+
+impl<'a> Camera for StereoCamera<'a> {
+    fn generate_ray(&self, sample: &CameraSample) -> (Ray, Float) {
+        let (eye, eye_sample) = self.eye_sample(sample);
+        match self.projection {
+            EyeProjection::Perspective(ref eyes) =>
+                eyes[eye_index(eye)].generate_ray(&eye_sample),
+            EyeProjection::OmniDirectional { ref camera_to_world, interaxial } =>
+                (self.omnidirectional_ray(camera_to_world, interaxial, eye, &eye_sample), 1.0),
+        }
+    }
+
+    fn generate_ray_differential(&self, sample: &CameraSample) -> (RayDifferential, Float) {
+        let (eye, eye_sample) = self.eye_sample(sample);
+        match self.projection {
+            EyeProjection::Perspective(ref eyes) =>
+                eyes[eye_index(eye)].generate_ray_differential(&eye_sample),
+            EyeProjection::OmniDirectional { ref camera_to_world, interaxial } => {
+                // Offset rays are computed in the eye's own raster so they never cross into the other view
+                let ray = self.omnidirectional_ray(camera_to_world, interaxial, eye, &eye_sample);
+                let rx = self.omnidirectional_ray(camera_to_world, interaxial, eye, &CameraSample { image_x: eye_sample.image_x + 1.0, ..eye_sample });
+                let ry = self.omnidirectional_ray(camera_to_world, interaxial, eye, &CameraSample { image_y: eye_sample.image_y + 1.0, ..eye_sample });
+
+                let rd = RayDifferential {
+                    ray,
+                    differentials: Some(RayDifferentials {
+                        rx_origin: rx.o,
+                        rx_direction: rx.d,
+                        ry_origin: ry.o,
+                        ry_direction: ry.d,
+                    }),
+                };
+
+                (rd, 1.0)
+            }
+        }
+    }
+
+    fn get_film(&mut self) -> &mut Film {
+        self.film
+    }
+}
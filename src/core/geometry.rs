@@ -1,7 +1,7 @@
 use cgmath::{Point3, Vector3};
-use core::types::{Float, INFINITY};
+use core::types::{Float, INFINITY, PI};
 use cgmath::{vec3, prelude::*};
-use core::math::lerp;
+use core::math::{clamp, lerp};
 use std::ops::MulAssign;
 use std::ops::Neg;
 
@@ -137,8 +137,8 @@ pub fn faceforward(n: Normal, n2: &Normal) -> Normal {
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct BBox {
-    p_min: Point3f,
-    p_max: Point3f,
+    pub p_min: Point3f,
+    pub p_max: Point3f,
 }
 
 impl BBox {
@@ -153,6 +153,13 @@ impl BBox {
         BBox { p_min: p, p_max: p }
     }
 
+    pub fn empty() -> BBox {
+        BBox {
+            p_min: Point3f::new(INFINITY, INFINITY, INFINITY),
+            p_max: Point3f::new(-INFINITY, -INFINITY, -INFINITY),
+        }
+    }
+
     pub fn union_point(&self, p: &Point3f) -> BBox {
         BBox {
             p_min: Point3f::new(self.p_min.x.min(p.x), self.p_min.y.min(p.y), self.p_min.z.min(p.z)),
@@ -247,3 +254,26 @@ impl BBox {
 pub fn spherical_direction(sintheta: Float, costheta: Float, phi: Float) -> Vector3f {
     vec3(sintheta * phi.cos(), sintheta * phi.sin(), costheta)
 }
+
+#[inline]
+pub fn spherical_theta(v: &Vector3f) -> Float {
+    clamp(v.z, -1.0, 1.0).acos()
+}
+
+#[inline]
+pub fn spherical_phi(v: &Vector3f) -> Float {
+    let p = v.y.atan2(v.x);
+    if p < 0.0 { p + 2.0 * PI } else { p }
+}
+
+pub fn coordinate_system(v1: &Vector3f) -> (Vector3f, Vector3f) {
+    let v2 = if v1.x.abs() > v1.y.abs() {
+        let invlen = 1.0 / (v1.x * v1.x + v1.z * v1.z).sqrt();
+        vec3(-v1.z * invlen, 0.0, v1.x * invlen)
+    } else {
+        let invlen = 1.0 / (v1.y * v1.y + v1.z * v1.z).sqrt();
+        vec3(0.0, v1.z * invlen, -v1.y * invlen)
+    };
+    let v3 = v1.cross(v2);
+    (v2, v3)
+}
@@ -0,0 +1,108 @@
+use core::spectrum::Spectrum;
+use core::types::Float;
+use image;
+use image::hdr::HDRDecoder;
+use image::ImageResult;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ImageWrap {
+    Repeat,
+    Black,
+    Clamp,
+}
+
+// Convert an sRGB encoded value in $[0,1]$ to linear
+pub fn inverse_gamma_correct(value: Float) -> Float {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub struct RGBImage {
+    width: u32,
+    height: u32,
+    texels: Vec<Spectrum>,
+}
+
+impl RGBImage {
+    pub fn new(width: u32, height: u32, texels: Vec<Spectrum>) -> RGBImage {
+        assert_eq!((width * height) as usize, texels.len());
+        RGBImage { width, height, texels }
+    }
+
+    // Radiance HDR files are read as linear values, other formats are scaled to $[0,1]$ without
+    // decoding their gamma
+    pub fn read(path: &str) -> ImageResult<RGBImage> {
+        let is_hdr = Path::new(path).extension().is_some_and(|e| e.eq_ignore_ascii_case("hdr"));
+
+        if is_hdr {
+            let decoder = HDRDecoder::new(BufReader::new(File::open(path)?))?;
+            let metadata = decoder.metadata();
+            let texels = decoder.read_image_hdr()?.iter()
+                .map(|p| Spectrum::new(p[0], p[1], p[2]))
+                .collect();
+            Ok(RGBImage::new(metadata.width, metadata.height, texels))
+        } else {
+            let img = image::open(path)?.to_rgb();
+            let (width, height) = img.dimensions();
+            let texels = img.pixels()
+                .map(|p| Spectrum::new(p[0] as Float, p[1] as Float, p[2] as Float) / 255.0)
+                .collect();
+            Ok(RGBImage::new(width, height, texels))
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn texel(&self, s: i32, t: i32, wrap: ImageWrap) -> Spectrum {
+        let (w, h) = (self.width as i32, self.height as i32);
+        let (s, t) = match wrap {
+            ImageWrap::Repeat => (s.rem_euclid(w), t.rem_euclid(h)),
+            ImageWrap::Clamp => (s.max(0).min(w - 1), t.max(0).min(h - 1)),
+            ImageWrap::Black => {
+                if s < 0 || s >= w || t < 0 || t >= h {
+                    return Spectrum::black();
+                }
+                (s, t)
+            }
+        };
+        self.texels[(t * w + s) as usize]
+    }
+
+    // Bilinearly interpolate texels around $(s,t)\in{}[0,1]^2$
+    pub fn lookup(&self, s: Float, t: Float, wrap: ImageWrap) -> Spectrum {
+        let s = s * self.width as Float - 0.5;
+        let t = t * self.height as Float - 0.5;
+        let s0 = s.floor();
+        let t0 = t.floor();
+        let ds = s - s0;
+        let dt = t - t0;
+        let (s0, t0) = (s0 as i32, t0 as i32);
+
+        (1.0 - ds) * (1.0 - dt) * self.texel(s0, t0, wrap) +
+            (1.0 - ds) * dt * self.texel(s0, t0 + 1, wrap) +
+            ds * (1.0 - dt) * self.texel(s0 + 1, t0, wrap) +
+            ds * dt * self.texel(s0 + 1, t0 + 1, wrap)
+    }
+
+    pub fn average(&self) -> Spectrum {
+        let sum: Spectrum = self.texels.iter().cloned().sum();
+        sum / (self.texels.len() as Float)
+    }
+
+    pub fn map<F: Fn(Spectrum) -> Spectrum>(&self, f: F) -> RGBImage {
+        RGBImage::new(self.width, self.height, self.texels.iter().map(|t| f(*t)).collect())
+    }
+}
+
@@ -4,6 +4,7 @@ use core::{
     geometry::{Ray, RayDifferential, RayDifferentials, Point3f, Normal, Vector3f},
     intersection::Intersection,
     light::{Light, LightSample, LightSampleOffsets, VisibilityTester, AreaLight},
+    lightdistrib::LightDistribution,
     montecarlo::{power_heuristic},
     spectrum::Spectrum,
     sampler::{Sample, Sampler},
@@ -187,32 +188,34 @@ pub fn uniform_sample_all_lights(scene: &Scene,
     l
 }
 
-pub fn uniform_sample_one_light(scene: &Scene,
-                                renderer: &Renderer,
-                                p: &Point3f,
-                                n: &Normal,
-                                wo: &Vector3f,
-                                ray_epsilon: Float,
-                                time: Float,
-                                bsdf: &BSDF,
-                                sample: &Sample,
-                                rng: &mut RNG,
-                                light_num_offset: Option<SampleOffset1d>,
-                                light_sample_offset: Option<&LightSampleOffsets>,
-                                bsdf_sample_offset: Option<&BSDFSampleOffsets>) -> Spectrum {
-    // Randomly choose a single light to sample, _light_
-    let n_lights = scene.lights.len();
-    if n_lights == 0 {
-        return Spectrum::black();
-    }
-
-    let mut light_num = if let Some(offset) = light_num_offset {
-        (sample[offset][0] * (n_lights as Float)).floor() as usize
+pub fn sample_one_light(scene: &Scene,
+                        renderer: &Renderer,
+                        p: &Point3f,
+                        n: &Normal,
+                        wo: &Vector3f,
+                        ray_epsilon: Float,
+                        time: Float,
+                        bsdf: &BSDF,
+                        sample: &Sample,
+                        rng: &mut RNG,
+                        light_distribution: &LightDistribution,
+                        light_num_offset: Option<SampleOffset1d>,
+                        light_sample_offset: Option<&LightSampleOffsets>,
+                        bsdf_sample_offset: Option<&BSDFSampleOffsets>) -> Spectrum {
+    // Choose a single light to sample, _light_, from _light_distribution_
+    let u = if let Some(offset) = light_num_offset {
+        sample[offset][0]
     } else {
-        (rng.random_float() * (n_lights as Float)).floor() as usize
+        rng.random_float()
     };
 
-    light_num = light_num.min(n_lights - 1);
+    let (light_num, light_pmf) = match light_distribution.sample(p, u) {
+        Some(choice) => choice,
+        None => return Spectrum::black(),
+    };
+    if light_pmf == 0.0 {
+        return Spectrum::black();
+    }
     let light = &scene.lights[light_num];
 
     // Initialize light and bsdf samples for single light sample
@@ -226,11 +229,9 @@ pub fn uniform_sample_one_light(scene: &Scene,
         bsdf_sample = BSDFSample::gen(rng);
     }
 
-    return (n_lights as Float) *
-        estimate_direct(scene, renderer, light.as_ref(), p, n, wo,
-                        ray_epsilon, time, bsdf, rng, &light_sample,
-                        &bsdf_sample, BxDFType::BSDF_ALL & !BxDFType::BSDF_SPECULAR);
-
+    estimate_direct(scene, renderer, light.as_ref(), p, n, wo,
+                    ray_epsilon, time, bsdf, rng, &light_sample,
+                    &bsdf_sample, BxDFType::BSDF_ALL & !BxDFType::BSDF_SPECULAR) / light_pmf
 }
 
 fn estimate_direct(scene: &Scene,
@@ -306,6 +307,7 @@ fn estimate_direct(scene: &Scene,
     ld
 }
 
+// Area lights are shared between _Scene::lights_ and their primitives, so lights are identified by address
 fn is_same_light(l1: &Light, l2: &AreaLight) -> bool {
-    unimplemented!()
-}
\ No newline at end of file
+    l1 as *const Light as *const () == l2 as *const AreaLight as *const ()
+}
@@ -34,7 +34,7 @@ impl<'a> Intersection<'a> {
 
     pub fn le(&self, w: Vector3f) -> Spectrum {
         match self.primitive.get_area_light() {
-            Some(area) => area.l(&self.dg.p, &self.dg.nn, &w),
+            Some(area) => area.l(&self.dg, &w),
             None => Spectrum::black()
         }
     }
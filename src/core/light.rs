@@ -7,9 +7,10 @@ use core::{
 use core::sampler::Sample;
 use core::renderer::Renderer;
 use core::rng::RNG;
-use core::geometry::{Ray, RayDifferential};
+use core::geometry::{Ray, RayDifferential, BBox};
 use core::geometry::distance;
-use core::geometry::Normal;
+use core::geometry::{Normal, coordinate_system};
+use core::differential_geometry::DifferentialGeometry;
 use core::shape::Shape;
 use std::sync::Arc;
 use core::montecarlo::Distribution1D;
@@ -36,10 +37,15 @@ pub trait Light {
     fn num_samples(&self) -> u32;
 
     fn is_delta_light(&self) -> bool;
+
+    // World space extent of the light's emitters, _None_ for lights at infinity
+    fn bound(&self) -> Option<BBox> {
+        None
+    }
 }
 
 pub trait AreaLight : Light {
-    fn l(&self, p: &Point3f, n: &Normal, w: &Vector3f) -> Spectrum;
+    fn l(&self, dg: &DifferentialGeometry, w: &Vector3f) -> Spectrum;
 }
 
 pub struct LightSample {
@@ -55,9 +61,9 @@ impl LightSample {
         ];
         let u_component = sample[offsets.component_offset][n];
 
-        debug_assert!(u_pos[0] >= 0.0 && u_pos[0] < 1.0);
-        debug_assert!(u_pos[1] >= 0.0 && u_pos[1] < 1.0);
-        debug_assert!(u_component >= 0.0 && u_component < 1.0);
+        debug_assert!((0.0..1.0).contains(&u_pos[0]));
+        debug_assert!((0.0..1.0).contains(&u_pos[1]));
+        debug_assert!((0.0..1.0).contains(&u_component));
 
         LightSample { u_pos, u_component }
     }
@@ -107,6 +113,12 @@ impl VisibilityTester {
         self.ray.as_ref().expect("no ray for VisibilityTester")
     }
 
+    pub fn set_ray(&mut self, p: Point3f, eps: Float, w: Vector3f, time: Float) {
+        let r = Ray::new(p, w, eps, INFINITY, time);
+        debug_assert!(!r.has_nans());
+        self.ray = Some(r);
+    }
+
     pub fn set_segment(&mut self, p1: Point3f, eps1: Float, p2: Point3f, eps2: Float, time: Float) {
         let dist = distance(&p1, &p2);
         let r = Ray::new(p1, (p2 - p1) / dist, eps1, dist * (1.0 - eps2), time);
@@ -148,22 +160,39 @@ impl ShapeSet {
         self.sum_area
     }
 
-    pub fn sample_point(&self, p: &Point3f, ls: &LightSample) -> (Point3f, Normal) {
+    pub fn world_bound(&self) -> BBox {
+        self.shapes.iter().fold(BBox::empty(), |b, s| b.union(&s.world_bound()))
+    }
+
+    pub fn sample_point(&self, p: &Point3f, ls: &LightSample) -> DifferentialGeometry<'_> {
         let (sn, _) = self.area_distribution.sample_discrete(ls.u_component);
-        let (pt, mut nn) = self.shapes[sn].as_ref().sample_point(p, ls.u_pos[0], ls.u_pos[1]);
+        let (pt, nn) = self.shapes[sn].as_ref().sample_point(p, ls.u_pos[0], ls.u_pos[1]);
 
         // Find closest intersection of ray with shapes in _ShapeSet_
-        let r = Ray::new(*p, pt-p, 1e-3, INFINITY, 0.0);
-        let mut thit = 1.0;
+        let mut r = Ray::new(*p, pt-p, 1e-3, INFINITY, 0.0);
+        let mut closest = None;
 
         for sh in &self.shapes {
-            if let Some((dg, th, _)) = sh.intersect(&r) {
-                nn = dg.nn;
-                thit = th;
+            if let Some((dg, thit, _)) = sh.intersect(&r) {
+                r.maxt = thit;
+                closest = Some(dg);
             }
         }
 
-        return (r.point_at(thit), nn);
+        closest.unwrap_or_else(|| point_geometry(pt, &nn, self.shapes[sn].as_ref()))
+    }
+
+    // Find surface geometry, including $(u,v)$, at a point sampled uniformly by area
+    pub fn sample_geometry(&self, ls: &LightSample) -> Option<DifferentialGeometry<'_>> {
+        let (sn, _) = self.area_distribution.sample_discrete(ls.u_component);
+        let shape = self.shapes[sn].as_ref();
+        let (pt, nn) = shape.sample(ls.u_pos[0], ls.u_pos[1]);
+
+        // Intersect the shape along the normal just above the sampled point
+        let bound = shape.world_bound();
+        let delta = 1e-3 * distance(&bound.p_min, &bound.p_max);
+        let r = Ray::new(pt + delta * nn.v, -nn.v, 0.0, 2.0 * delta, 0.0);
+        shape.intersect(&r).map(|(dg, _, _)| dg)
     }
 
     pub fn sample(&self, ls: &LightSample, ns: &Normal) -> (Point3f, Normal) {
@@ -179,3 +208,10 @@ impl ShapeSet {
         pdf / self.sum_area
     }
 }
+
+// Differential geometry at a point known only by its position and surface normal
+fn point_geometry<'a>(p: Point3f, n: &Normal, shape: &'a Shape) -> DifferentialGeometry<'a> {
+    let (dpdu, dpdv) = coordinate_system(&n.v);
+    let dn = Normal::new(0.0, 0.0, 0.0);
+    DifferentialGeometry::new(p, dpdu, dpdv, dn, dn, 0.0, 0.0, shape)
+}
@@ -0,0 +1,198 @@
+use core::{
+    geometry::{BBox, Point3f, distance_squared},
+    montecarlo::Distribution1D,
+    scene::Scene,
+    types::Float,
+};
+use cgmath::prelude::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LightSampleStrategy {
+    Uniform,
+    Power,
+    Spatial,
+}
+
+// Chooses a single light to sample at a shading point
+pub trait LightDistribution {
+    // Returns the index of the chosen light in _Scene::lights_ and the probability of choosing it
+    fn sample(&self, p: &Point3f, u: Float) -> Option<(usize, Float)>;
+}
+
+pub fn create_light_distribution(strategy: LightSampleStrategy, scene: &Scene) -> Box<LightDistribution> {
+    match strategy {
+        LightSampleStrategy::Uniform => Box::new(UniformLightDistribution::new(scene)),
+        LightSampleStrategy::Power => Box::new(PowerLightDistribution::new(scene)),
+        LightSampleStrategy::Spatial => Box::new(LightTreeDistribution::new(scene)),
+    }
+}
+
+pub struct UniformLightDistribution {
+    n_lights: usize,
+}
+
+impl UniformLightDistribution {
+    pub fn new(scene: &Scene) -> UniformLightDistribution {
+        UniformLightDistribution { n_lights: scene.lights.len() }
+    }
+}
+
+impl LightDistribution for UniformLightDistribution {
+    fn sample(&self, _p: &Point3f, u: Float) -> Option<(usize, Float)> {
+        if self.n_lights == 0 {
+            return None;
+        }
+        let light_num = ((u * self.n_lights as Float).floor() as usize).min(self.n_lights - 1);
+        Some((light_num, 1.0 / self.n_lights as Float))
+    }
+}
+
+pub struct PowerLightDistribution {
+    distribution: Option<Distribution1D>,
+}
+
+impl PowerLightDistribution {
+    pub fn new(scene: &Scene) -> PowerLightDistribution {
+        let mut power: Vec<Float> = scene.lights.iter().map(|l| l.power(scene).y().max(0.0)).collect();
+
+        // Fall back to uniform selection if no light reports any power
+        if power.iter().all(|&p| p == 0.0) {
+            power.iter_mut().for_each(|p| *p = 1.0);
+        }
+
+        let distribution = if power.is_empty() { None } else { Some(Distribution1D::new(&power)) };
+        PowerLightDistribution { distribution }
+    }
+}
+
+impl LightDistribution for PowerLightDistribution {
+    fn sample(&self, _p: &Point3f, u: Float) -> Option<(usize, Float)> {
+        self.distribution.as_ref().map(|d| d.sample_discrete(u))
+    }
+}
+
+enum LightTreeNode {
+    Leaf { bound: BBox, power: Float, light: usize },
+    Interior { bound: BBox, power: Float, children: [usize; 2] },
+}
+
+impl LightTreeNode {
+    fn bound_and_power(&self) -> (&BBox, Float) {
+        match *self {
+            LightTreeNode::Leaf { ref bound, power, .. } => (bound, power),
+            LightTreeNode::Interior { ref bound, power, .. } => (bound, power),
+        }
+    }
+
+    // Estimate contribution of the lights below this node at _p_ from their power and distance
+    fn importance(&self, p: &Point3f) -> Float {
+        let (bound, power) = self.bound_and_power();
+        let center = bound.p_min + 0.5 * (bound.p_max - bound.p_min);
+        let half_diagonal2 = 0.25 * (bound.p_max - bound.p_min).magnitude2();
+        power / distance_squared(p, &center).max(half_diagonal2).max(1e-6)
+    }
+}
+
+// Bounding volume hierarchy over lights with finite extent. Lights without a bound, such as
+// infinite and distant lights, are chosen uniformly next to the tree as a whole.
+pub struct LightTreeDistribution {
+    nodes: Vec<LightTreeNode>,
+    infinite_lights: Vec<usize>,
+}
+
+impl LightTreeDistribution {
+    pub fn new(scene: &Scene) -> LightTreeDistribution {
+        let mut infinite_lights = vec![];
+        let mut bounded = vec![];
+        for (i, light) in scene.lights.iter().enumerate() {
+            match light.bound() {
+                Some(bound) => {
+                    let power = light.power(scene).y();
+                    if power > 0.0 {
+                        bounded.push((i, bound, power));
+                    }
+                }
+                None => infinite_lights.push(i),
+            }
+        }
+
+        let mut nodes = Vec::with_capacity(2 * bounded.len());
+        if !bounded.is_empty() {
+            build_light_tree(&mut bounded, &mut nodes);
+        }
+
+        LightTreeDistribution { nodes, infinite_lights }
+    }
+}
+
+// Recursively build tree over _lights_ and return the index of its root in _nodes_
+fn build_light_tree(lights: &mut [(usize, BBox, Float)], nodes: &mut Vec<LightTreeNode>) -> usize {
+    let bound = lights.iter().fold(BBox::empty(), |b, l| b.union(&l.1));
+    let power = lights.iter().map(|l| l.2).sum();
+
+    if lights.len() == 1 {
+        nodes.push(LightTreeNode::Leaf { bound, power, light: lights[0].0 });
+        return nodes.len() - 1;
+    }
+
+    // Partition lights at the median centroid along the axis of largest extent
+    let centroid_bound = lights.iter().fold(BBox::empty(), |b, l| {
+        b.union_point(&(l.1.p_min + 0.5 * (l.1.p_max - l.1.p_min)))
+    });
+    let axis = centroid_bound.maximum_extent() as usize;
+    let centroid = |l: &(usize, BBox, Float)| 0.5 * (l.1.p_min[axis] + l.1.p_max[axis]);
+    lights.sort_by(|a, b| centroid(a).partial_cmp(&centroid(b)).unwrap());
+
+    let node = nodes.len();
+    nodes.push(LightTreeNode::Interior { bound, power, children: [0, 0] });
+    let mid = lights.len() / 2;
+    let (left, right) = lights.split_at_mut(mid);
+    let children = [build_light_tree(left, nodes), build_light_tree(right, nodes)];
+    if let LightTreeNode::Interior { children: ref mut c, .. } = nodes[node] {
+        *c = children;
+    }
+    node
+}
+
+impl LightDistribution for LightTreeDistribution {
+    fn sample(&self, p: &Point3f, u: Float) -> Option<(usize, Float)> {
+        // Choose between infinite lights and the light tree
+        let n_infinite = self.infinite_lights.len();
+        let n_choices = n_infinite + if self.nodes.is_empty() { 0 } else { 1 };
+        if n_choices == 0 {
+            return None;
+        }
+        let p_infinite = n_infinite as Float / n_choices as Float;
+        let mut u = u;
+        if u < p_infinite {
+            let index = ((u * n_choices as Float).floor() as usize).min(n_infinite - 1);
+            return Some((self.infinite_lights[index], 1.0 / n_choices as Float));
+        }
+        u = ((u - p_infinite) / (1.0 - p_infinite)).min(1.0 - Float::EPSILON);
+
+        // Traverse light tree choosing children by importance
+        let mut pmf = 1.0 - p_infinite;
+        let mut node = 0;
+        loop {
+            match self.nodes[node] {
+                LightTreeNode::Leaf { light, .. } => return Some((light, pmf)),
+                LightTreeNode::Interior { children, .. } => {
+                    let ci = [self.nodes[children[0]].importance(p), self.nodes[children[1]].importance(p)];
+                    if ci[0] == 0.0 && ci[1] == 0.0 {
+                        return None;
+                    }
+                    let p0 = ci[0] / (ci[0] + ci[1]);
+                    if u < p0 {
+                        node = children[0];
+                        u = (u / p0).min(1.0 - Float::EPSILON);
+                        pmf *= p0;
+                    } else {
+                        node = children[1];
+                        u = ((u - p0) / (1.0 - p0)).min(1.0 - Float::EPSILON);
+                        pmf *= 1.0 - p0;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,17 +1,93 @@
 use core::{
     differential_geometry::DifferentialGeometry,
-    geometry::Normal,
+    geometry::{Normal, Vector3f},
     reflection::BSDF,
+    spectrum::Spectrum,
     texture::Texture,
     types::Float
 };
 use core::geometry::faceforward;
 use cgmath::prelude::*;
+use std::sync::Arc;
 
 pub trait Material {
     fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a>;
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NormalMapSpace {
+    // Normals relative to the frame of $\dpdu$ and the shading normal, $z$ pointing away from the surface
+    Tangent,
+    // Normals in the object space of the shape
+    Object,
+}
+
+// Shading normals stored in the RGB channels of a texture, each component mapped from $[0,1]$ to $[-1,1]$
+pub struct NormalMap {
+    map: Arc<Texture<Spectrum>>,
+    space: NormalMapSpace,
+}
+
+impl NormalMap {
+    pub fn new(map: Arc<Texture<Spectrum>>, space: NormalMapSpace) -> NormalMap {
+        NormalMap { map, space }
+    }
+}
+
+// Apply the optional bump and normal maps of a material to the shading geometry
+pub fn shading_geometry<'a>(bump_map: &Option<Arc<Texture<Float>>>,
+                            normal_map: &Option<Arc<NormalMap>>,
+                            dg_geom: &DifferentialGeometry,
+                            dg_shading: &DifferentialGeometry<'a>) -> DifferentialGeometry<'a> {
+    let dgs = bump_map.as_ref().map_or_else(|| dg_shading.clone(), |b| bump(b.as_ref(), dg_geom, dg_shading));
+    match *normal_map {
+        Some(ref n) => normal_mapping(n, dg_geom, &dgs),
+        None => dgs,
+    }
+}
+
+pub fn normal_mapping<'a>(n: &NormalMap, dg_geom: &DifferentialGeometry, dgs: &DifferentialGeometry<'a>) -> DifferentialGeometry<'a> {
+    // Decode normal from the texture
+    let c = n.map.evaluate(dgs);
+    let v = Vector3f::new(2.0 * c.r - 1.0, 2.0 * c.g - 1.0, 2.0 * c.b - 1.0);
+
+    // Transform normal to world space
+    let ns = match n.space {
+        NormalMapSpace::Tangent => {
+            let t = dgs.dpdu - dgs.nn.v.dot(dgs.dpdu) * dgs.nn.v;
+            if t.magnitude2() == 0.0 {
+                return dgs.clone();
+            }
+            let t = t.normalize();
+
+            // Follow the direction of $v$ so that mirrored texture coordinates keep the green channel
+            let b = dgs.nn.v.cross(t);
+            let b = if dgs.dpdv.dot(b) < 0.0 { -b } else { b };
+            v.x * t + v.y * b + v.z * dgs.nn.v
+        }
+        NormalMapSpace::Object => dgs.shape.get_object_to_world().transform_normal(Normal::from(v)).v,
+    };
+    if ns.magnitude2() == 0.0 {
+        return dgs.clone();
+    }
+    let ns = ns.normalize();
+
+    // Rebuild shading frame around the new normal, keeping the lengths of the partial derivatives
+    let mut dg_map = dgs.clone();
+    let dpdu = dgs.dpdu - ns.dot(dgs.dpdu) * ns;
+    if dpdu.magnitude2() == 0.0 {
+        return dgs.clone();
+    }
+    dg_map.dpdu = dpdu.normalize() * dgs.dpdu.magnitude();
+    let dpdv = ns.cross(dg_map.dpdu).normalize() * dgs.dpdv.magnitude();
+    dg_map.dpdv = if dgs.dpdv.dot(dpdv) < 0.0 { -dpdv } else { dpdv };
+
+    // Orient shading normal to match geometric normal
+    dg_map.nn = faceforward(Normal::from(ns), &dg_geom.nn);
+
+    dg_map
+}
+
 pub fn bump<'a>(d: &Texture<Float>, dg_geom: &DifferentialGeometry, dgs: &DifferentialGeometry<'a>) -> DifferentialGeometry<'a> {
     // Compute offset positions and evaluate displacement texture
     let mut dg_eval = dgs.clone();
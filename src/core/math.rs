@@ -24,6 +24,11 @@ pub fn radians(degrees: Float) -> Float {
     (PI / 180.0) * degrees
 }
 
+#[inline]
+pub fn degrees(radians: Float) -> Float {
+    (180.0 / PI) * radians
+}
+
 #[inline]
 pub fn clamp(val: Float, low: Float, high: Float) -> Float {
     val.max(low).min(high)
@@ -39,3 +44,36 @@ pub fn lerp(t: Float, v1: Float, v2: Float) -> Float {
     (1.0 - t) * v1 + t * v2
 }
 
+
+// Error function approximation from Abramowitz and Stegun 7.1.26, coefficients as published
+#[allow(clippy::excessive_precision)]
+pub fn erf(x: Float) -> Float {
+    let (a1, a2, a3, a4, a5) = (0.254829592, -0.284496736, 1.421413741, -1.453152027, 1.061405429);
+    let p = 0.3275911;
+
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+    let t = 1.0 / (1.0 + p * x);
+    let y = 1.0 - (((((a5 * t + a4) * t) + a3) * t + a2) * t + a1) * t * (-x * x).exp();
+    sign * y
+}
+
+// Inverse error function from Giles' single precision approximation, coefficients as published
+// in "Approximating the erfinv function" (GPU Computing Gems, 2011)
+#[allow(clippy::excessive_precision)]
+pub fn erf_inv(x: Float) -> Float {
+    let x = clamp(x, -0.99999, 0.99999);
+    let mut w = -((1.0 - x) * (1.0 + x)).ln();
+    let p = if w < 5.0 {
+        w -= 2.5;
+        [3.43273939e-07, -3.5233877e-06, -4.39150654e-06, 0.00021858087, -0.00125372503,
+            -0.00417768164, 0.246640727, 1.50140941]
+            .iter().fold(2.81022636e-08, |p, c| c + p * w)
+    } else {
+        w = w.sqrt() - 3.0;
+        [0.000100950558, 0.00134934322, -0.00367342844, 0.00573950773, -0.0076224613,
+            0.00943887047, 1.00167406, 2.83297682]
+            .iter().fold(-0.000200214257, |p, c| c + p * w)
+    };
+    p * x
+}
@@ -3,12 +3,15 @@ pub mod camera;
 pub mod film;
 pub mod filter;
 pub mod geometry;
+pub mod imageio;
 pub mod integrator;
 pub mod intersection;
 pub mod light;
+pub mod lightdistrib;
 pub mod material;
 pub mod math;
 pub mod montecarlo;
+pub mod photometry;
 pub mod primitive;
 pub mod reflection;
 pub mod renderer;
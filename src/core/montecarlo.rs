@@ -1,7 +1,8 @@
 use core::geometry::Vector3f;
 use core::types::Float;
 use core::types::PI;
-use cgmath::vec3;
+use core::math::{clamp, lerp};
+use cgmath::{vec3, prelude::*};
 use superslice::*;
 
 pub fn cosine_sample_hemisphere(u1: Float, u2: Float) -> Vector3f {
@@ -19,6 +20,82 @@ pub fn uniform_sample_sphere(u1: Float, u2: Float) -> Vector3f {
     return vec3(x, y, z);
 }
 
+pub fn uniform_sample_cone(u1: Float, u2: Float, costhetamax: Float, x: &Vector3f, y: &Vector3f, z: &Vector3f) -> Vector3f {
+    let costheta = lerp(u1, costhetamax, 1.0);
+    let sintheta = (1.0 - costheta * costheta).max(0.0).sqrt();
+    let phi = u2 * 2.0 * PI;
+    phi.cos() * sintheta * x + phi.sin() * sintheta * y + costheta * z
+}
+
+#[inline]
+pub fn uniform_cone_pdf(costhetamax: Float) -> Float {
+    1.0 / (2.0 * PI * (1.0 - costhetamax))
+}
+
+// Returns barycentrics $(b_1,b_2)$ of a point sampled uniformly by area
+pub fn uniform_sample_triangle(u1: Float, u2: Float) -> (Float, Float) {
+    let su1 = u1.sqrt();
+    (1.0 - su1, u2 * su1)
+}
+
+// Sample a direction uniformly inside the spherical triangle with unit vertices _a_, _b_
+// and _c_ (Arvo 1995). Returns the direction and its solid angle density.
+pub fn uniform_sample_spherical_triangle(u1: Float, u2: Float, a: &Vector3f, b: &Vector3f, c: &Vector3f) -> Option<(Vector3f, Float)> {
+    let (alpha, beta, gamma) = spherical_triangle_angles(a, b, c)?;
+    let area = alpha + beta + gamma - PI;
+
+    // Choose sub-triangle area $A'$ and find vertex $\VEC{c'}$ on edge $ac$
+    let ap_pi = lerp(u1, PI, alpha + beta + gamma);
+    let (sin_alpha, cos_alpha) = alpha.sin_cos();
+    let (sin_ap, cos_ap) = ap_pi.sin_cos();
+    let sin_phi = sin_ap * cos_alpha - cos_ap * sin_alpha;
+    let cos_phi = cos_ap * cos_alpha + sin_ap * sin_alpha;
+    let k1 = cos_phi + cos_alpha;
+    let k2 = sin_phi - sin_alpha * a.dot(*b);
+    let cos_bp = clamp((k2 + (k2 * cos_phi - k1 * sin_phi) * cos_alpha) / ((k2 * sin_phi + k1 * cos_phi) * sin_alpha), -1.0, 1.0);
+    let sin_bp = (1.0 - cos_bp * cos_bp).max(0.0).sqrt();
+    let cp = cos_bp * a + sin_bp * gram_schmidt(c, a).normalize();
+
+    // Sample direction along the arc between $\VEC{b}$ and $\VEC{c'}$
+    let cos_theta = 1.0 - u2 * (1.0 - cp.dot(*b));
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    let w = cos_theta * b + sin_theta * gram_schmidt(&cp, b).normalize();
+
+    Some((w.normalize(), 1.0 / area))
+}
+
+// Vertex angles of the spherical triangle with unit vertices _a_, _b_ and _c_, or _None_ if it is
+// degenerate and cannot be sampled by _uniform_sample_spherical_triangle_
+pub fn spherical_triangle_angles(a: &Vector3f, b: &Vector3f, c: &Vector3f) -> Option<(Float, Float, Float)> {
+    // Compute normals of the great circles through the triangle edges
+    let (n_ab, n_bc, n_ca) = (a.cross(*b), b.cross(*c), c.cross(*a));
+    if n_ab.magnitude2() == 0.0 || n_bc.magnitude2() == 0.0 || n_ca.magnitude2() == 0.0 {
+        return None;
+    }
+    let (n_ab, n_bc, n_ca) = (n_ab.normalize(), n_bc.normalize(), n_ca.normalize());
+
+    // Find vertex angles and check for positive spherical excess
+    let alpha = angle_between(&n_ab, &-n_ca);
+    let beta = angle_between(&n_bc, &-n_ab);
+    let gamma = angle_between(&n_ca, &-n_bc);
+    if alpha + beta + gamma - PI <= 0.0 {
+        return None;
+    }
+    Some((alpha, beta, gamma))
+}
+
+fn angle_between(v1: &Vector3f, v2: &Vector3f) -> Float {
+    if v1.dot(*v2) < 0.0 {
+        PI - 2.0 * clamp((v1 + v2).magnitude() / 2.0, -1.0, 1.0).asin()
+    } else {
+        2.0 * clamp((v2 - v1).magnitude() / 2.0, -1.0, 1.0).asin()
+    }
+}
+
+fn gram_schmidt(v: &Vector3f, w: &Vector3f) -> Vector3f {
+    v - v.dot(*w) * w
+}
+
 pub fn concentric_sample_disk(u1: Float, u2: Float) -> (Float, Float) {
     // Map uniform random numbers to $[-1,1]^2$
     let sx = 2.0 * u1 - 1.0;
@@ -92,9 +169,18 @@ impl Distribution1D {
         Distribution1D { func, cdf, func_int }
     }
 
-    pub fn sample_continuous(&self, u: Float) -> (Float, Float, usize) {
+    pub fn count(&self) -> usize {
+        self.func.len()
+    }
+
+    fn find_segment(&self, u: Float) -> usize {
         // Find surrounding CDF segments and _offset_
         let offset = self.cdf.upper_bound_by(|y| y.partial_cmp(&u).unwrap());
+        offset.max(1).min(self.func.len()) - 1
+    }
+
+    pub fn sample_continuous(&self, u: Float) -> (Float, Float, usize) {
+        let offset = self.find_segment(u);
 
         debug_assert!(offset < self.func.len());
         debug_assert!(u >= self.cdf[offset] && u < self.cdf[offset+1]);
@@ -113,8 +199,7 @@ impl Distribution1D {
     }
 
     pub fn sample_discrete(&self, u: Float) -> (usize, Float) {
-        // Find surrounding CDF segments and _offset_
-        let offset = self.cdf.upper_bound_by(|y| y.partial_cmp(&u).unwrap());
+        let offset = self.find_segment(u);
         debug_assert!(offset < self.func.len());
         debug_assert!(u >= self.cdf[offset] && u < self.cdf[offset+1]);
         let pdf = self.func[offset] / (self.func_int * (self.func.len() as Float));
@@ -123,6 +208,44 @@ impl Distribution1D {
     }
 }
 
+pub struct Distribution2D {
+    conditional_v: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    pub fn new(func: &[Float], nu: usize, nv: usize) -> Distribution2D {
+        debug_assert_eq!(func.len(), nu * nv);
+
+        // Compute conditional sampling distribution for $\tilde{v}$
+        let conditional_v: Vec<Distribution1D> = func.chunks(nu).map(Distribution1D::new).collect();
+
+        // Compute marginal sampling distribution $p[\tilde{v}]$
+        let marginal_func: Vec<Float> = conditional_v.iter().map(|d| d.func_int).collect();
+        let marginal = Distribution1D::new(&marginal_func);
+
+        Distribution2D { conditional_v, marginal }
+    }
+
+    pub fn sample_continuous(&self, u0: Float, u1: Float) -> ([Float; 2], Float) {
+        let (v, pdf1, iv) = self.marginal.sample_continuous(u1);
+        let (u, pdf0, _) = self.conditional_v[iv].sample_continuous(u0);
+        ([u, v], pdf0 * pdf1)
+    }
+
+    pub fn pdf(&self, u: Float, v: Float) -> Float {
+        let nu = self.conditional_v[0].count();
+        let nv = self.marginal.count();
+        let iu = ((u * nu as Float) as usize).min(nu - 1);
+        let iv = ((v * nv as Float) as usize).min(nv - 1);
+        if self.conditional_v[iv].func_int * self.marginal.func_int == 0.0 {
+            return 0.0;
+        }
+        (self.conditional_v[iv].func[iu] * self.marginal.func[iv]) /
+            (self.conditional_v[iv].func_int * self.marginal.func_int)
+    }
+}
+
 #[inline]
 pub fn power_heuristic(nf: i32, f_pdf: Float, ng: i32, g_pdf: Float) -> Float {
     let f = (nf as Float) * f_pdf;
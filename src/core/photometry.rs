@@ -0,0 +1,335 @@
+use core::math::{degrees, lerp};
+use core::types::{Float, PI};
+use std::fs;
+use std::io;
+
+// Luminous efficacy in $lm/W$ relating photometric and radiometric units
+pub const LUMINOUS_EFFICACY: Float = 683.0;
+
+enum HorizontalSymmetry {
+    Rotational,
+    Quadrant,
+    Bilateral,
+    BilateralC90,
+    Full,
+}
+
+// Type C photometric web. Vertical angles are measured in degrees from the nadir and horizontal
+// angles in degrees around the vertical axis. Intensities are in candela.
+pub struct PhotometricData {
+    vertical_angles: Vec<Float>,
+    horizontal_angles: Vec<Float>,
+    candela: Vec<Float>,
+    symmetry: HorizontalSymmetry,
+}
+
+impl PhotometricData {
+    // _candela_ holds one run of vertical samples for each horizontal angle
+    pub fn new(vertical_angles: Vec<Float>, mut horizontal_angles: Vec<Float>, mut candela: Vec<Float>) -> PhotometricData {
+        let nv = vertical_angles.len();
+        assert!(nv > 0 && !horizontal_angles.is_empty());
+        assert_eq!(candela.len(), nv * horizontal_angles.len());
+
+        // Determine symmetry of the distribution from the range of horizontal angles
+        let first = horizontal_angles[0];
+        let last = horizontal_angles[horizontal_angles.len() - 1];
+        let symmetry = if horizontal_angles.len() == 1 {
+            HorizontalSymmetry::Rotational
+        } else if first == 0.0 && last == 90.0 {
+            HorizontalSymmetry::Quadrant
+        } else if first == 0.0 && last == 180.0 {
+            HorizontalSymmetry::Bilateral
+        } else if first == 90.0 && last == 270.0 {
+            HorizontalSymmetry::BilateralC90
+        } else {
+            HorizontalSymmetry::Full
+        };
+
+        // Close full distributions that stop short of $360$ degrees so that lookups wrap around
+        if let HorizontalSymmetry::Full = symmetry {
+            if first == 0.0 && last < 360.0 {
+                horizontal_angles.push(360.0);
+                let plane = candela[..nv].to_vec();
+                candela.extend(plane);
+            }
+        }
+
+        PhotometricData { vertical_angles, horizontal_angles, candela, symmetry }
+    }
+
+    // Read an IES LM-63 file; only type C photometry is supported
+    pub fn read_ies(path: &str) -> io::Result<PhotometricData> {
+        PhotometricData::parse_ies(&String::from_utf8_lossy(&fs::read(path)?))
+    }
+
+    pub fn parse_ies(text: &str) -> io::Result<PhotometricData> {
+        let mut lines = text.lines();
+
+        // Skip keywords up to the TILT line
+        let tilt = loop {
+            match lines.next() {
+                Some(line) if line.trim_start().starts_with("TILT=") => break line.trim()[5..].trim().to_string(),
+                Some(_) => continue,
+                None => return Err(invalid_data("missing TILT line in IES file")),
+            }
+        };
+
+        let mut tokens = lines
+            .flat_map(|line| line.split(|c: char| c.is_whitespace() || c == ','))
+            .filter(|token| !token.is_empty());
+        let mut next = || tokens.next().ok_or_else(|| invalid_data("unexpected end of IES file")).and_then(parse_float);
+
+        // Skip lamp tilt data, the tilt of the luminaire is not modeled
+        if tilt == "INCLUDE" {
+            next()?;
+            let num_pairs = next()? as usize;
+            for _ in 0..2 * num_pairs {
+                next()?;
+            }
+        }
+
+        // Read luminaire parameters
+        let _num_lamps = next()?;
+        let _lumens_per_lamp = next()?;
+        let multiplier = next()?;
+        let num_vertical = next()? as usize;
+        let num_horizontal = next()? as usize;
+        let photometric_type = next()?;
+        let _units_type = next()?;
+        let (_width, _length, _height) = (next()?, next()?, next()?);
+        let ballast_factor = next()?;
+        let ballast_lamp_factor = next()?;
+        let _input_watts = next()?;
+        if photometric_type != 1.0 {
+            return Err(invalid_data("only type C photometry is supported"));
+        }
+
+        // Read angles and candela values
+        let vertical = (0..num_vertical).map(|_| next()).collect::<io::Result<Vec<_>>>()?;
+        let horizontal = (0..num_horizontal).map(|_| next()).collect::<io::Result<Vec<_>>>()?;
+        let scale = multiplier * ballast_factor * ballast_lamp_factor;
+        let candela = (0..num_vertical * num_horizontal)
+            .map(|_| next().map(|c| c * scale))
+            .collect::<io::Result<Vec<_>>>()?;
+
+        check_angles(&vertical)?;
+        check_angles(&horizontal)?;
+        Ok(PhotometricData::new(vertical, horizontal, candela))
+    }
+
+    // Read an EULUMDAT (.ldt) file
+    pub fn read_eulumdat(path: &str) -> io::Result<PhotometricData> {
+        PhotometricData::parse_eulumdat(&String::from_utf8_lossy(&fs::read(path)?))
+    }
+
+    pub fn parse_eulumdat(text: &str) -> io::Result<PhotometricData> {
+        let lines: Vec<&str> = text.lines().map(|line| line.trim()).collect();
+
+        // Lines are numbered from one as in the format specification
+        let line = |i: usize| {
+            lines.get(i - 1).ok_or_else(|| invalid_data("unexpected end of EULUMDAT file")).and_then(|s| parse_float(s))
+        };
+
+        let symmetry = line(3)? as u32;
+        let num_c = line(4)? as usize;
+        let num_gamma = line(6)? as usize;
+        let conversion_factor = line(24)?;
+        let num_lamp_sets = line(26)? as usize;
+        let mut flux = 0.0;
+        for set in 0..num_lamp_sets {
+            flux += line(29 + 6 * set)?;
+        }
+
+        // Read C-plane and gamma angles following the direct ratios
+        let start = 27 + 6 * num_lamp_sets + 10;
+        let c_angles = (0..num_c).map(|i| line(start + i)).collect::<io::Result<Vec<_>>>()?;
+        let gamma = (0..num_gamma).map(|i| line(start + num_c + i)).collect::<io::Result<Vec<_>>>()?;
+
+        // Find the range of C-planes stored for the file's symmetry
+        let (mc1, mc2) = match symmetry {
+            0 => (1, num_c),
+            1 => (1, 1),
+            2 => (1, num_c / 2 + 1),
+            3 => (3 * num_c / 4 + 1, 3 * num_c / 4 + 1 + num_c / 2),
+            4 => (1, num_c / 4 + 1),
+            _ => return Err(invalid_data("invalid EULUMDAT symmetry indicator")),
+        };
+        if num_c == 0 || mc2 - mc1 >= num_c {
+            return Err(invalid_data("invalid number of EULUMDAT C-planes"));
+        }
+
+        // Read intensities in $cd/klm$ and convert them to candela
+        let scale = conversion_factor * flux / 1000.0;
+        let intensities_start = start + num_c + num_gamma;
+        let mut planes = Vec::with_capacity(mc2 - mc1 + 1);
+        for (k, mc) in (mc1..mc2 + 1).enumerate() {
+            let plane = (0..num_gamma)
+                .map(|i| line(intensities_start + k * num_gamma + i).map(|c| c * scale))
+                .collect::<io::Result<Vec<_>>>()?;
+            planes.push((c_angles[(mc - 1) % num_c], plane));
+        }
+
+        // Planes symmetric about C90-C270 run from C270 through C0 to C90, mirror them onto C90 to C270
+        if symmetry == 3 {
+            for plane in &mut planes {
+                plane.0 = (180.0 - plane.0).rem_euclid(360.0);
+            }
+            planes.reverse();
+        }
+
+        let horizontal = planes.iter().map(|p| p.0).collect::<Vec<_>>();
+        let candela = planes.into_iter().flat_map(|p| p.1).collect::<Vec<_>>();
+
+        check_angles(&gamma)?;
+        check_angles(&horizontal)?;
+        Ok(PhotometricData::new(gamma, horizontal, candela))
+    }
+
+    // Map a horizontal angle into the range covered by the measurements
+    fn fold_horizontal(&self, phi: Float) -> Float {
+        let phi = phi.rem_euclid(360.0);
+        match self.symmetry {
+            HorizontalSymmetry::Rotational => self.horizontal_angles[0],
+            HorizontalSymmetry::Quadrant => {
+                let phi = phi % 180.0;
+                if phi > 90.0 { 180.0 - phi } else { phi }
+            }
+            HorizontalSymmetry::Bilateral => if phi > 180.0 { 360.0 - phi } else { phi },
+            HorizontalSymmetry::BilateralC90 => {
+                if phi < 90.0 {
+                    180.0 - phi
+                } else if phi > 270.0 {
+                    540.0 - phi
+                } else {
+                    phi
+                }
+            }
+            HorizontalSymmetry::Full => phi,
+        }
+    }
+
+    // Bilinearly interpolate intensity at the given angles in degrees
+    pub fn intensity(&self, vertical: Float, horizontal: Float) -> Float {
+        let nv = self.vertical_angles.len();
+        let nh = self.horizontal_angles.len();
+        if vertical < self.vertical_angles[0] || vertical > self.vertical_angles[nv - 1] {
+            return 0.0;
+        }
+
+        let (v0, dv) = find_interval(&self.vertical_angles, vertical);
+        let (h0, dh) = find_interval(&self.horizontal_angles, self.fold_horizontal(horizontal));
+        let (v1, h1) = ((v0 + 1).min(nv - 1), (h0 + 1).min(nh - 1));
+        let value = |h: usize, v: usize| self.candela[h * nv + v];
+
+        lerp(dh, lerp(dv, value(h0, v0), value(h0, v1)), lerp(dv, value(h1, v0), value(h1, v1)))
+    }
+
+    // Integrate intensity over the sphere of directions to find the luminous flux in lumens
+    pub fn luminous_flux(&self) -> Float {
+        let (n_theta, n_phi) = (180, 360);
+        let dtheta = PI / n_theta as Float;
+        let dphi = 2.0 * PI / n_phi as Float;
+        let mut flux = 0.0;
+        for i in 0..n_theta {
+            let theta = (i as Float + 0.5) * dtheta;
+            for j in 0..n_phi {
+                let phi = (j as Float + 0.5) * dphi;
+                flux += self.intensity(degrees(theta), degrees(phi)) * theta.sin() * dtheta * dphi;
+            }
+        }
+        flux
+    }
+}
+
+// Find the segment of sorted _angles_ containing _x_ and the offset within it
+fn find_interval(angles: &[Float], x: Float) -> (usize, Float) {
+    let n = angles.len();
+    if n == 1 || x <= angles[0] {
+        return (0, 0.0);
+    }
+    if x >= angles[n - 1] {
+        return (n - 1, 0.0);
+    }
+    let i = angles.partition_point(|&a| a <= x) - 1;
+    (i, (x - angles[i]) / (angles[i + 1] - angles[i]))
+}
+
+fn check_angles(angles: &[Float]) -> io::Result<()> {
+    if angles.is_empty() || angles.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(invalid_data("photometric angles must be increasing"));
+    }
+    Ok(())
+}
+
+fn parse_float(s: &str) -> io::Result<Float> {
+    s.replace(',', ".").parse().map_err(|_| invalid_data("invalid number in photometric data"))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: Float, expected: Float) {
+        assert!((value - expected).abs() <= 1e-3 * expected, "{} != {}", value, expected);
+    }
+
+    // Bilateral symmetry about C0-C180, constant over vertical angles and rising linearly from
+    // 100 cd at C0 to 300 cd at C180 after the multiplier of 2
+    const IES_BILATERAL: &str = "IESNA:LM-63-2002
+[TEST] bilateral fixture
+TILT=NONE
+1 1000 2.0 3 3 1 2 0.5 0.5 0.0
+1.0 1.0 100
+0 90 180
+0 90 180
+50 50 50
+100 100 100
+150 150 150
+";
+
+    #[test]
+    fn parse_ies_bilateral() {
+        let data = PhotometricData::parse_ies(IES_BILATERAL).unwrap();
+
+        assert_close(data.intensity(0.0, 0.0), 100.0);
+        assert_close(data.intensity(45.0, 45.0), 150.0);
+        assert_close(data.intensity(90.0, 270.0), 200.0);
+        assert_close(data.intensity(135.0, 315.0), 150.0);
+
+        // $\int_0^\pi \sin\theta \, d\theta \cdot 2 \int_0^\pi (100 + 200 \phi / \pi) \, d\phi = 800 \pi$
+        assert_close(data.luminous_flux(), 800.0 * PI);
+    }
+
+    // Quadrant symmetry with C0 and C90 stored out of four C-planes, 100 and 300 cd/klm for a
+    // lamp of 2000 lm
+    fn eulumdat_quadrant() -> String {
+        let mut lines = vec![
+            "Test", "1", "4", "4", "90", "3", "90", "report", "luminaire", "number", "quadrant.ldt",
+            "date", "100", "100", "50", "100", "100", "0", "0", "0", "0", "100", "100", "1.0", "0",
+            "1", "1", "lamp", "2000", "3000", "80", "20",
+        ];
+        lines.extend(vec!["0.5"; 10]);
+        lines.extend(vec!["0", "90", "180", "270"]);
+        lines.extend(vec!["0", "90", "180"]);
+        lines.extend(vec!["100", "100", "100"]);
+        lines.extend(vec!["300", "300", "300"]);
+        lines.join("\r\n")
+    }
+
+    #[test]
+    fn parse_eulumdat_quadrant() {
+        let data = PhotometricData::parse_eulumdat(&eulumdat_quadrant()).unwrap();
+
+        assert_close(data.intensity(0.0, 0.0), 200.0);
+        assert_close(data.intensity(60.0, 45.0), 400.0);
+        assert_close(data.intensity(60.0, 135.0), 400.0);
+        assert_close(data.intensity(120.0, 300.0), 200.0 + 400.0 * 60.0 / 90.0);
+
+        // $\int_0^\pi \sin\theta \, d\theta \cdot 4 \int_0^{\pi/2} (200 + 800 \phi / \pi) \, d\phi = 1600 \pi$
+        assert_close(data.luminous_flux(), 1600.0 * PI);
+    }
+}
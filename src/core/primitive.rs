@@ -1,5 +1,5 @@
 use core::{
-    geometry::Ray,
+    geometry::{Ray, BBox},
     intersection::Intersection,
     material::Material,
     shape::Shape,
@@ -13,6 +13,7 @@ use std::sync::Arc;
 pub trait Primitive {
     fn intersect(&self, ray: &mut Ray) -> Option<Intersection>;
     fn intersect_p(&self, ray: &Ray) -> bool;
+    fn world_bound(&self) -> BBox;
     fn get_bsdf<'a, 'b>(&'a self, dg: &'a DifferentialGeometry<'a>, object_to_world: &'b Transform) -> BSDF<'a>;
     fn get_area_light(&self) -> Option<&AreaLight>;
 }
@@ -52,6 +53,10 @@ impl Primitive for GeometricPrimitive {
         self.shape.intersect_p(ray)
     }
 
+    fn world_bound(&self) -> BBox {
+        self.shape.world_bound()
+    }
+
     fn get_bsdf<'a, 'b>(&'a self, dg: &'a DifferentialGeometry<'a>, object_to_world: &'b Transform) -> BSDF<'a> {
         let dgs = self.shape.get_shading_geometry(object_to_world, dg);
         self.material.get_bsdf(dg, dgs)
@@ -94,6 +99,10 @@ impl Primitive for CompoundPrimitive {
         self.primitives.iter().any(|p| { p.intersect_p(ray) })
     }
 
+    fn world_bound(&self) -> BBox {
+        self.primitives.iter().fold(BBox::empty(), |b, p| { b.union(&p.world_bound()) })
+    }
+
     fn get_bsdf<'a, 'b>(&'a self, dg: &'a DifferentialGeometry<'a>, object_to_world: &'b Transform) -> BSDF<'a> {
         panic!("get_bsdf should not be called for Aggregate")
     }
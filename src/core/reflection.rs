@@ -8,7 +8,7 @@ use core::montecarlo::cosine_sample_hemisphere;
 use core::math::floor_to_int;
 use core::types::PI;
 use core::geometry::spherical_direction;
-use core::math::clamp;
+use core::math::{clamp, radians, erf, erf_inv};
 use core::sampler::Sample;
 use core::sampler::SampleOffset1d;
 use core::sampler::SampleOffset2d;
@@ -160,6 +160,17 @@ impl<'a> BSDF<'a> {
         self.bxdfs.push(bxdf)
     }
 
+    // Scale the contribution of all lobes, as when blending BSDFs
+    pub fn scale(&mut self, s: Spectrum) {
+        let bxdfs = self.bxdfs.drain(..).map(|b| Box::new(ScaledBxDF::new(b, s)) as Box<BxDF>).collect();
+        self.bxdfs = bxdfs;
+    }
+
+    // Take the lobes out of the BSDF so that they can be combined into another one
+    pub fn into_bxdfs(self) -> Vec<Box<BxDF>> {
+        self.bxdfs
+    }
+
     fn world_to_local(&self, v: &Vector3f) -> Vector3f {
         vec3(v.dot(self.sn), v.dot(self.tn), v.dot(self.nn.v))
     }
@@ -188,8 +199,27 @@ pub trait BxDF {
             0.0
         }
     }
+
+    // Hemispherical-directional reflectance for _wo_, estimated with stratified samples of the BxDF
+    fn rho(&self, wo: &Vector3f) -> Spectrum {
+        let n = RHO_SAMPLES;
+        let mut r = Spectrum::black();
+        for i in 0..n * n {
+            let u1 = ((i % n) as Float + 0.5) / n as Float;
+            let u2 = ((i / n) as Float + 0.5) / n as Float;
+            let mut wi = vec3(0.0, 0.0, 1.0);
+            let (f, pdf) = self.sample_f(*wo, &mut wi, u1, u2);
+            if pdf > 0.0 {
+                r += f * abs_cos_theta(&wi) / pdf;
+            }
+        }
+        r / (n * n) as Float
+    }
 }
 
+// Number of stratified samples per dimension used by the default _BxDF::rho_ estimate
+const RHO_SAMPLES: usize = 4;
+
 pub struct SpecularReflection {
     r: Spectrum,
     fresnel: Box<Fresnel>,
@@ -283,6 +313,39 @@ impl BxDF for SpecularTransmission {
     }
 }
 
+// Transmission straight through a thin surface such as a windowpane or soap bubble, passing the
+// light that _fresnel_ does not reflect without refracting it
+pub struct ThinDielectricTransmission {
+    t: Spectrum,
+    fresnel: Box<Fresnel>,
+}
+
+impl ThinDielectricTransmission {
+    pub fn new(t: Spectrum, fresnel: Box<Fresnel>) -> ThinDielectricTransmission {
+        ThinDielectricTransmission { t, fresnel }
+    }
+}
+
+impl BxDF for ThinDielectricTransmission {
+    fn f(&self, _wo: &Vector3f, _wi: &Vector3f) -> Spectrum {
+        Spectrum::black()
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, _u1: Float, _u2: Float) -> (Spectrum, Float) {
+        *wi = -wo;
+        let f = self.fresnel.evaluate(cos_theta(&wo));
+        ((Spectrum::white() - f) * self.t / abs_cos_theta(wi), 1.0)
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        BxDFType::BSDF_TRANSMISSION | BxDFType::BSDF_SPECULAR
+    }
+
+    fn pdf(&self, _wo: &Vector3f, _wi: &Vector3f) -> Float {
+        0.0
+    }
+}
+
 pub struct Lambertian {
     r: Spectrum
 }
@@ -315,6 +378,139 @@ impl BxDF for Lambertian {
     fn bxdf_type(&self) -> BxDFType {
         BxDFType::BSDF_REFLECTION | BxDFType::BSDF_DIFFUSE
     }
+
+    fn rho(&self, _wo: &Vector3f) -> Spectrum {
+        self.r
+    }
+}
+
+// Diffuse transmission through a thin surface, scattering into the opposite hemisphere
+pub struct LambertianTransmission {
+    t: Spectrum
+}
+
+impl LambertianTransmission {
+    pub fn new(t: Spectrum) -> LambertianTransmission {
+        LambertianTransmission { t }
+    }
+}
+
+impl BxDF for LambertianTransmission {
+    fn f(&self, _wo: &Vector3f, _wi: &Vector3f) -> Spectrum {
+        self.t * INV_PI
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        // Cosine-sample the hemisphere on the other side of the surface
+        *wi = cosine_sample_hemisphere(u1, u2);
+        if wo.z > 0.0 {
+            wi.z *= -1.0
+        }
+        (self.f(&wo, wi), self.pdf(&wo, wi))
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        BxDFType::BSDF_TRANSMISSION | BxDFType::BSDF_DIFFUSE
+    }
+
+    fn pdf(&self, wo: &Vector3f, wi: &Vector3f) -> Float {
+        if same_hemisphere(wo, wi) { 0.0 } else { abs_cos_theta(wi) * INV_PI }
+    }
+
+    fn rho(&self, _wo: &Vector3f) -> Spectrum {
+        self.t
+    }
+}
+
+pub struct OrenNayar {
+    r: Spectrum,
+    a: Float,
+    b: Float,
+}
+
+impl OrenNayar {
+    // _sigma_ is the standard deviation of the microfacet orientation angle in degrees
+    pub fn new(r: Spectrum, sigma: Float) -> OrenNayar {
+        let sigma = radians(sigma);
+        let sigma2 = sigma * sigma;
+        let a = 1.0 - (sigma2 / (2.0 * (sigma2 + 0.33)));
+        let b = 0.45 * sigma2 / (sigma2 + 0.09);
+        OrenNayar { r, a, b }
+    }
+}
+
+impl BxDF for OrenNayar {
+    fn f(&self, wo: &Vector3f, wi: &Vector3f) -> Spectrum {
+        let sinthetai = sin_theta(wi);
+        let sinthetao = sin_theta(wo);
+
+        // Compute cosine term of Oren-Nayar model
+        let mut maxcos = 0.0;
+        if sinthetai > 1e-4 && sinthetao > 1e-4 {
+            let sinphii = sin_phi(wi);
+            let cosphii = cos_phi(wi);
+            let sinphio = sin_phi(wo);
+            let cosphio = cos_phi(wo);
+            let dcos = cosphii * cosphio + sinphii * sinphio;
+            maxcos = dcos.max(0.0);
+        }
+
+        // Compute sine and tangent terms of Oren-Nayar model
+        let (sinalpha, tanbeta) = if abs_cos_theta(wi) > abs_cos_theta(wo) {
+            (sinthetao, sinthetai / abs_cos_theta(wi))
+        } else {
+            (sinthetai, sinthetao / abs_cos_theta(wo))
+        };
+
+        self.r * INV_PI * (self.a + self.b * maxcos * sinalpha * tanbeta)
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        // Cosine-sample the hemisphere, flipping the direction if necessary
+        *wi = cosine_sample_hemisphere(u1, u2);
+        if wo.z < 0.0 {
+            wi.z *= -1.0
+        }
+        (self.f(&wo, wi), self.pdf(&wo, wi))
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        BxDFType::BSDF_REFLECTION | BxDFType::BSDF_DIFFUSE
+    }
+}
+
+pub struct ScaledBxDF {
+    bxdf: Box<BxDF>,
+    s: Spectrum,
+}
+
+impl ScaledBxDF {
+    pub fn new(bxdf: Box<BxDF>, s: Spectrum) -> ScaledBxDF {
+        ScaledBxDF { bxdf, s }
+    }
+}
+
+impl BxDF for ScaledBxDF {
+    fn f(&self, wo: &Vector3f, wi: &Vector3f) -> Spectrum {
+        self.s * self.bxdf.f(wo, wi)
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        let (f, pdf) = self.bxdf.sample_f(wo, wi, u1, u2);
+        (self.s * f, pdf)
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        self.bxdf.bxdf_type()
+    }
+
+    fn pdf(&self, wo: &Vector3f, wi: &Vector3f) -> Float {
+        self.bxdf.pdf(wo, wi)
+    }
+
+    fn rho(&self, wo: &Vector3f) -> Spectrum {
+        self.s * self.bxdf.rho(wo)
+    }
 }
 
 pub struct BSDFSample {
@@ -364,8 +560,47 @@ impl BSDFSampleOffsets {
 
 pub trait MicrofacetDistribution {
     fn d(&self, wh: &Vector3f) -> Float;
-    fn sample_f(&self, wo: &Vector3f, u1: Float, u2: Float) -> (Vector3f, Float);
-    fn pdf(&self, wo: &Vector3f, wi: &Vector3f) -> Float;
+
+    // Sample a microfacet normal in the hemisphere of _wo_
+    fn sample_wh(&self, wo: &Vector3f, u1: Float, u2: Float) -> Vector3f;
+
+    // Solid angle density of normals returned by _sample_wh_
+    fn pdf_wh(&self, wo: &Vector3f, wh: &Vector3f) -> Float;
+
+    // Masking-shadowing term, the V-cavity model unless the distribution provides Smith's
+    fn g(&self, wo: &Vector3f, wi: &Vector3f, wh: &Vector3f) -> Float {
+        let n_dot_wh = abs_cos_theta(wh);
+        let n_dot_wo = abs_cos_theta(wo);
+        let n_dot_wi = abs_cos_theta(wi);
+        let wo_dot_wh = wo.dot(*wh).abs();
+
+        (2.0 * n_dot_wh * n_dot_wo / wo_dot_wh).min(2.0 * n_dot_wh * n_dot_wi / wo_dot_wh).min(1.0)
+    }
+
+    fn sample_f(&self, wo: &Vector3f, u1: Float, u2: Float) -> (Vector3f, Float) {
+        let wh = self.sample_wh(wo, u1, u2);
+
+        // Compute incident direction by reflecting about $\wh$
+        let wi = reflect(wo, &wh);
+        if wo.dot(wh) <= 0.0 {
+            return (wi, 0.0);
+        }
+        (wi, self.pdf(wo, &wi))
+    }
+
+    fn pdf(&self, wo: &Vector3f, wi: &Vector3f) -> Float {
+        // Convert density of $\wh$ to density of the reflected direction
+        match half_vector(wo, wi) {
+            Some(wh) if wo.dot(wh) > 0.0 => self.pdf_wh(wo, &wh) / (4.0 * wo.dot(wh)),
+            _ => 0.0,
+        }
+    }
+}
+
+// Map perceptual roughness in $[0,1]$, as used by common asset formats, to microfacet $\alpha$
+pub fn roughness_to_alpha(roughness: Float) -> Float {
+    let roughness = roughness.max(1e-3);
+    roughness * roughness
 }
 
 pub struct Blinn {
@@ -384,37 +619,283 @@ impl MicrofacetDistribution for Blinn {
         return (self.exponent + 2.0) * INV_TWO_PI * costhetah.powf(self.exponent);
     }
 
-    fn sample_f(&self, wo: &Vector3f, u1: Float, u2: Float) -> (Vector3f, Float) {
+    fn sample_wh(&self, wo: &Vector3f, u1: Float, u2: Float) -> Vector3f {
         // Compute sampled half-angle vector $\wh$ for Blinn distribution
         let costheta = u1.powf(1.0 / (self.exponent + 1.0));
         let sintheta = (1.0 - costheta * costheta).max(0.0).sqrt();
         let phi = u2 * 2.0 * PI;
-        let mut wh = spherical_direction(sintheta, costheta, phi);
-        if !same_hemisphere(wo, &wh) {
-            wh = -wh;
+        let wh = spherical_direction(sintheta, costheta, phi);
+        if same_hemisphere(wo, &wh) { wh } else { -wh }
+    }
+
+    fn pdf_wh(&self, _wo: &Vector3f, wh: &Vector3f) -> Float {
+        // Compute PDF for $\wh$ from Blinn distribution
+        (self.exponent + 1.0) * abs_cos_theta(wh).powf(self.exponent) * INV_TWO_PI
+    }
+}
+
+// Ashikhmin-Shirley distribution with separate exponents along $\VEC{s}$ and $\VEC{t}$
+pub struct Anisotropic {
+    ex: Float,
+    ey: Float,
+}
+
+impl Anisotropic {
+    pub fn new(ex: Float, ey: Float) -> Anisotropic {
+        Anisotropic { ex: ex.min(10000.0), ey: ey.min(10000.0) }
+    }
+
+    fn sample_first_quadrant(&self, u1: Float, u2: Float) -> (Float, Float) {
+        let phi = if self.ex == self.ey {
+            PI * u1 * 0.5
+        } else {
+            (((self.ex + 1.0) / (self.ey + 1.0)).sqrt() * (PI * u1 * 0.5).tan()).atan()
+        };
+        let (sinphi, cosphi) = phi.sin_cos();
+        let costheta = u2.powf(1.0 / (self.ex * cosphi * cosphi + self.ey * sinphi * sinphi + 1.0));
+        (phi, costheta)
+    }
+
+    fn exponent(&self, wh: &Vector3f) -> Option<Float> {
+        let ds = 1.0 - cos_theta(wh) * cos_theta(wh);
+        if ds > 0.0 {
+            Some((self.ex * wh.x * wh.x + self.ey * wh.y * wh.y) / ds)
+        } else {
+            None
         }
+    }
+}
 
-        // Compute incident direction by reflecting about $\wh$
-        let wi = -1.0 * wo + 2.0 * wo.dot(wh) * wh;
+impl MicrofacetDistribution for Anisotropic {
+    fn d(&self, wh: &Vector3f) -> Float {
+        match self.exponent(wh) {
+            Some(e) => ((self.ex + 2.0) * (self.ey + 2.0)).sqrt() * INV_TWO_PI * abs_cos_theta(wh).powf(e),
+            None => 0.0,
+        }
+    }
 
-        // Compute PDF for $\wi$ from Blinn distribution
-        let mut blinn_pdf = ((self.exponent + 1.0) * costheta.powf(self.exponent)) / (2.0 * PI * 4.0 * wo.dot(wh));
-        if wo.dot(wh) <= 0.0 {
-            blinn_pdf = 0.0;
+    fn sample_wh(&self, wo: &Vector3f, u1: Float, u2: Float) -> Vector3f {
+        // Sample from first quadrant and remap to hemisphere to sample $\wh$
+        let (phi, costheta) = if u1 < 0.25 {
+            self.sample_first_quadrant(4.0 * u1, u2)
+        } else if u1 < 0.5 {
+            let (phi, costheta) = self.sample_first_quadrant(4.0 * (0.5 - u1), u2);
+            (PI - phi, costheta)
+        } else if u1 < 0.75 {
+            let (phi, costheta) = self.sample_first_quadrant(4.0 * (u1 - 0.5), u2);
+            (phi + PI, costheta)
+        } else {
+            let (phi, costheta) = self.sample_first_quadrant(4.0 * (1.0 - u1), u2);
+            (2.0 * PI - phi, costheta)
+        };
+        let sintheta = (1.0 - costheta * costheta).max(0.0).sqrt();
+        let wh = spherical_direction(sintheta, costheta, phi);
+        if same_hemisphere(wo, &wh) { wh } else { -wh }
+    }
+
+    fn pdf_wh(&self, _wo: &Vector3f, wh: &Vector3f) -> Float {
+        match self.exponent(wh) {
+            Some(e) => ((self.ex + 1.0) * (self.ey + 1.0)).sqrt() * INV_TWO_PI * abs_cos_theta(wh).powf(e),
+            None => 0.0,
         }
+    }
+}
 
-        (wi, blinn_pdf)
+pub struct TrowbridgeReitz {
+    alphax: Float,
+    alphay: Float,
+}
+
+impl TrowbridgeReitz {
+    // _alphax_ and _alphay_ give the roughness along $\VEC{s}$ and $\VEC{t}$ of the shading frame
+    pub fn new(alphax: Float, alphay: Float) -> TrowbridgeReitz {
+        TrowbridgeReitz { alphax: alphax.max(1e-4), alphay: alphay.max(1e-4) }
     }
 
-    fn pdf(&self, wo: &Vector3f, wi: &Vector3f) -> Float {
-        let wh = (wo + wi).normalize();
-        let costheta = abs_cos_theta(&wh);
-        // Compute PDF for $\wi$ from Blinn distribution
-        let mut blinn_pdf = ((self.exponent + 1.0) * costheta.powf(self.exponent)) / (2.0 * PI * 4.0 * wo.dot(wh));
-        if wo.dot(wh) <= 0.0 {
-            blinn_pdf = 0.0;
+    // Ratio of hidden to visible microfacet area in the Smith model
+    fn lambda(&self, w: &Vector3f) -> Float {
+        let abs_tan_theta = tan_theta2(w).sqrt();
+        if abs_tan_theta.is_infinite() {
+            return 0.0;
         }
-        return blinn_pdf;
+
+        // Compute _alpha_ for direction _w_
+        let alpha = (cos2_phi(w) * self.alphax * self.alphax + sin2_phi(w) * self.alphay * self.alphay).sqrt();
+        let alpha2_tan2_theta = (alpha * abs_tan_theta) * (alpha * abs_tan_theta);
+        (-1.0 + (1.0 + alpha2_tan2_theta).sqrt()) / 2.0
+    }
+
+    fn g1(&self, w: &Vector3f) -> Float {
+        1.0 / (1.0 + self.lambda(w))
+    }
+}
+
+impl MicrofacetDistribution for TrowbridgeReitz {
+    fn d(&self, wh: &Vector3f) -> Float {
+        let tan2_theta = tan_theta2(wh);
+        if tan2_theta.is_infinite() {
+            return 0.0;
+        }
+        let cos4_theta = cos_theta(wh).powi(4);
+        let e = (cos2_phi(wh) / (self.alphax * self.alphax) + sin2_phi(wh) / (self.alphay * self.alphay)) * tan2_theta;
+        1.0 / (PI * self.alphax * self.alphay * cos4_theta * (1.0 + e) * (1.0 + e))
+    }
+
+    // Sample microfacet normal from the distribution of normals visible from _wo_
+    fn sample_wh(&self, wo: &Vector3f, u1: Float, u2: Float) -> Vector3f {
+        let flip = wo.z < 0.0;
+        let wo = if flip { -*wo } else { *wo };
+
+        // Transform _wo_ to the hemisphere configuration
+        let vh = vec3(self.alphax * wo.x, self.alphay * wo.y, wo.z).normalize();
+
+        // Construct orthonormal basis around _vh_
+        let lensq = vh.x * vh.x + vh.y * vh.y;
+        let t1 = if lensq > 0.0 { vec3(-vh.y, vh.x, 0.0) / lensq.sqrt() } else { vec3(1.0, 0.0, 0.0) };
+        let t2 = vh.cross(t1);
+
+        // Sample projected area of the visible hemisphere
+        let r = u1.sqrt();
+        let phi = 2.0 * PI * u2;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).max(0.0).sqrt() + s * r * phi.sin();
+
+        // Reproject onto the hemisphere and transform normal back to the ellipsoid configuration
+        let nh = p1 * t1 + p2 * t2 + (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt() * vh;
+        let wh = vec3(self.alphax * nh.x, self.alphay * nh.y, nh.z.max(1e-6)).normalize();
+        if flip { -wh } else { wh }
+    }
+
+    fn pdf_wh(&self, wo: &Vector3f, wh: &Vector3f) -> Float {
+        if cos_theta(wo) == 0.0 {
+            return 0.0;
+        }
+        self.d(wh) * self.g1(wo) * wo.dot(*wh).abs() / abs_cos_theta(wo)
+    }
+
+    fn g(&self, wo: &Vector3f, wi: &Vector3f, _wh: &Vector3f) -> Float {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+}
+
+pub struct Beckmann {
+    alphax: Float,
+    alphay: Float,
+}
+
+impl Beckmann {
+    // _alphax_ and _alphay_ give the roughness along $\VEC{s}$ and $\VEC{t}$ of the shading frame
+    pub fn new(alphax: Float, alphay: Float) -> Beckmann {
+        Beckmann { alphax: alphax.max(1e-4), alphay: alphay.max(1e-4) }
+    }
+
+    // Rational approximation of the Smith $\Lambda$ function for Beckmann
+    fn lambda(&self, w: &Vector3f) -> Float {
+        let abs_tan_theta = tan_theta2(w).sqrt();
+        if abs_tan_theta.is_infinite() {
+            return 0.0;
+        }
+
+        // Compute _alpha_ for direction _w_
+        let alpha = (cos2_phi(w) * self.alphax * self.alphax + sin2_phi(w) * self.alphay * self.alphay).sqrt();
+        let a = 1.0 / (alpha * abs_tan_theta);
+        if a >= 1.6 {
+            return 0.0;
+        }
+        (1.0 - 1.259 * a + 0.396 * a * a) / (3.535 * a + 2.181 * a * a)
+    }
+
+    fn g1(&self, w: &Vector3f) -> Float {
+        1.0 / (1.0 + self.lambda(w))
+    }
+}
+
+// Sample slopes of the visible normals of a unit roughness Beckmann surface seen at _cos_theta_i_
+fn beckmann_sample_11(cos_theta_i: Float, u1: Float, u2: Float) -> (Float, Float) {
+    // Special case (normal incidence)
+    if cos_theta_i > 0.9999 {
+        let r = (-(1.0 - u1).ln()).sqrt();
+        let phi = 2.0 * PI * u2;
+        return (r * phi.cos(), r * phi.sin());
+    }
+
+    let inv_sqrt_pi = 1.0 / PI.sqrt();
+    let sin_theta_i = (1.0 - cos_theta_i * cos_theta_i).max(0.0).sqrt();
+    let tan_theta_i = sin_theta_i / cos_theta_i;
+    let cot_theta_i = 1.0 / tan_theta_i;
+
+    // Search interval for the inverted slope CDF
+    let mut a = -1.0;
+    let mut c = erf(cot_theta_i);
+    let sample_x = u1.max(1e-6);
+
+    // Start from a fitted guess of the inverse
+    let theta_i = cos_theta_i.acos();
+    let fit = 1.0 + theta_i * (-0.876 + theta_i * (0.4265 - 0.0594 * theta_i));
+    let mut b = c - (1.0 + c) * (1.0 - sample_x).powf(fit);
+
+    // Normalization factor for the CDF
+    let normalization = 1.0 / (1.0 + c + inv_sqrt_pi * tan_theta_i * (-cot_theta_i * cot_theta_i).exp());
+
+    // Invert the CDF with safeguarded Newton-Raphson iterations
+    for _ in 0..10 {
+        if !(b >= a && b <= c) {
+            b = 0.5 * (a + c);
+        }
+
+        let inv_erf = erf_inv(b);
+        let value = normalization * (1.0 + b + inv_sqrt_pi * tan_theta_i * (-inv_erf * inv_erf).exp()) - sample_x;
+        let derivative = normalization * (1.0 - inv_erf * tan_theta_i);
+        if value.abs() < 1e-5 {
+            break;
+        }
+
+        if value > 0.0 { c = b; } else { a = b; }
+        b -= value / derivative;
+    }
+
+    (erf_inv(b), erf_inv(2.0 * u2.max(1e-6) - 1.0))
+}
+
+impl MicrofacetDistribution for Beckmann {
+    fn d(&self, wh: &Vector3f) -> Float {
+        let tan2_theta = tan_theta2(wh);
+        if tan2_theta.is_infinite() {
+            return 0.0;
+        }
+        let cos4_theta = cos_theta(wh).powi(4);
+        let e = (cos2_phi(wh) / (self.alphax * self.alphax) + sin2_phi(wh) / (self.alphay * self.alphay)) * tan2_theta;
+        (-e).exp() / (PI * self.alphax * self.alphay * cos4_theta)
+    }
+
+    // Sample microfacet normal from the distribution of normals visible from _wo_
+    fn sample_wh(&self, wo: &Vector3f, u1: Float, u2: Float) -> Vector3f {
+        let flip = wo.z < 0.0;
+        let wo = if flip { -*wo } else { *wo };
+
+        // Stretch _wo_ to unit roughness
+        let wo_stretched = vec3(self.alphax * wo.x, self.alphay * wo.y, wo.z).normalize();
+
+        // Sample slopes for unit roughness and rotate them to the azimuth of _wo_
+        let (slope_x, slope_y) = beckmann_sample_11(cos_theta(&wo_stretched), u1, u2);
+        let (cos_phi, sin_phi) = (cos_phi(&wo_stretched), sin_phi(&wo_stretched));
+        let (slope_x, slope_y) = (cos_phi * slope_x - sin_phi * slope_y, sin_phi * slope_x + cos_phi * slope_y);
+
+        // Unstretch slopes and compute normal
+        let wh = vec3(-self.alphax * slope_x, -self.alphay * slope_y, 1.0).normalize();
+        if flip { -wh } else { wh }
+    }
+
+    fn pdf_wh(&self, wo: &Vector3f, wh: &Vector3f) -> Float {
+        if cos_theta(wo) == 0.0 {
+            return 0.0;
+        }
+        self.d(wh) * self.g1(wo) * wo.dot(*wh).abs() / abs_cos_theta(wo)
+    }
+
+    fn g(&self, wo: &Vector3f, wi: &Vector3f, _wh: &Vector3f) -> Float {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
     }
 }
 
@@ -485,6 +966,97 @@ impl Fresnel for FresnelNoOp {
     }
 }
 
+// Reflectance of a thin dielectric slab, summing the infinite series of incoherent internal
+// reflections between its two parallel faces
+pub struct FresnelThinDielectric {
+    eta: Float,
+}
+
+impl FresnelThinDielectric {
+    pub fn new(eta: Float) -> FresnelThinDielectric {
+        FresnelThinDielectric { eta }
+    }
+}
+
+impl Fresnel for FresnelThinDielectric {
+    fn evaluate(&self, cosi: Float) -> Spectrum {
+        // Both faces are entered from outside the slab
+        let r = FresnelDielectric::new(1.0, self.eta).evaluate(cosi.abs()).r;
+        if r >= 1.0 {
+            return Spectrum::white();
+        }
+        let t = 1.0 - r;
+        Spectrum::from(r + t * t * r / (1.0 - r * r))
+    }
+}
+
+// Wavelengths in nanometers at which interference is evaluated for the red, green and blue channels
+const THIN_FILM_WAVELENGTHS: [Float; 3] = [630.0, 532.0, 465.0];
+
+// Reflectance of a dielectric film of _thickness_ nanometers and index _eta_film_ between media of
+// index _eta_i_ and _eta_t_, including interference between waves reflected by its two faces
+pub struct FresnelThinFilm {
+    thickness: Float,
+    eta_film: Float,
+    eta_i: Float,
+    eta_t: Float,
+}
+
+impl FresnelThinFilm {
+    pub fn new(thickness: Float, eta_film: Float, eta_i: Float, eta_t: Float) -> FresnelThinFilm {
+        FresnelThinFilm { thickness, eta_film, eta_i, eta_t }
+    }
+}
+
+impl Fresnel for FresnelThinFilm {
+    fn evaluate(&self, cosi: Float) -> Spectrum {
+        // Compute indices of refraction for the incident side
+        let cos1 = clamp(cosi.abs(), 0.0, 1.0);
+        let (e1, e2) = (if cosi > 0.0 { self.eta_i } else { self.eta_t }, self.eta_film);
+        let e3 = if cosi > 0.0 { self.eta_t } else { self.eta_i };
+
+        // Compute angles inside the film and the transmitting medium using Snell's law
+        let sin1 = (1.0 - cos1 * cos1).max(0.0).sqrt();
+        let sin2 = e1 / e2 * sin1;
+        let sin3 = e1 / e3 * sin1;
+        if sin2 >= 1.0 || sin3 >= 1.0 {
+            // Handle total internal reflection, the lossless film returns all light
+            return Spectrum::white();
+        }
+        let cos2 = (1.0 - sin2 * sin2).max(0.0).sqrt();
+        let cos3 = (1.0 - sin3 * sin3).max(0.0).sqrt();
+
+        // Compute amplitude reflection coefficients at both faces for each polarization
+        let r12 = [(e1 * cos1 - e2 * cos2) / (e1 * cos1 + e2 * cos2), (e2 * cos1 - e1 * cos2) / (e2 * cos1 + e1 * cos2)];
+        let r23 = [(e2 * cos2 - e3 * cos3) / (e2 * cos2 + e3 * cos3), (e3 * cos2 - e2 * cos3) / (e3 * cos2 + e2 * cos3)];
+
+        // Sum reflected waves with the phase difference accumulated crossing the film twice
+        let reflectance = |wavelength: Float| {
+            let cos_delta = (4.0 * PI * e2 * self.thickness * cos2 / wavelength).cos();
+            let mut r = 0.0;
+            for p in 0..2 {
+                let (a, b) = (r12[p], r23[p]);
+                r += (a * a + b * b + 2.0 * a * b * cos_delta) / (1.0 + a * a * b * b + 2.0 * a * b * cos_delta);
+            }
+            0.5 * r
+        };
+
+        Spectrum::new(reflectance(THIN_FILM_WAVELENGTHS[0]),
+                      reflectance(THIN_FILM_WAVELENGTHS[1]),
+                      reflectance(THIN_FILM_WAVELENGTHS[2]))
+    }
+}
+
+// Fitted hemispherical average of dielectric Fresnel reflectance for light inside a medium of
+// relative index _eta_, including total internal reflection
+pub fn fresnel_diffuse_reflectance(eta: Float) -> Float {
+    if eta < 1.0 {
+        -0.4399 + 0.7099 / eta - 0.3319 / (eta * eta) + 0.0636 / (eta * eta * eta)
+    } else {
+        -1.4399 / (eta * eta) + 0.7099 / eta + 0.6681 + 0.0636 * eta
+    }
+}
+
 fn fr_diel(cosi: Float, cost: Float, etai: Spectrum, etat: Spectrum) -> Spectrum {
     let rparl = ((etat * cosi) - (etai * cost)) / ((etat * cosi) + (etai * cost));
     let rperp = ((etai * cosi) - (etat * cost)) / ((etai * cosi) + (etat * cost));
@@ -512,15 +1084,6 @@ impl Microfacet {
     pub fn new(reflectance: Spectrum, fresnel: Box<Fresnel>, distribution: Box<MicrofacetDistribution>) -> Microfacet {
         Microfacet { reflectance, fresnel, distribution }
     }
-
-    fn g(&self, wo: &Vector3f, wi: &Vector3f, wh: &Vector3f) -> Float {
-        let n_dot_wh = abs_cos_theta(wh);
-        let n_dot_wo = abs_cos_theta(wo);
-        let n_dot_wi = abs_cos_theta(wi);
-        let wo_dot_wh = wo.dot(*wh).abs();
-
-        (2.0 * n_dot_wh * n_dot_wo / wo_dot_wh).min(2.0 * n_dot_wh * n_dot_wi / wo_dot_wh).min(1.0)
-    }
 }
 
 impl BxDF for Microfacet {
@@ -536,16 +1099,21 @@ impl BxDF for Microfacet {
             return Spectrum::black();
         }
 
+        // Orient $\wh$ to the side of the shading normal for dielectric Fresnel
         wh = wh.normalize();
+        if wh.z < 0.0 {
+            wh = -wh;
+        }
         let cos_theta_h = wi.dot(wh);
         let f = self.fresnel.evaluate(cos_theta_h);
-        return self.reflectance * self.distribution.d(&wh) * self.g(wo, wi, &wh) * f / (4.0 * cos_theta_i * cos_theta_o);
+        self.reflectance * self.distribution.d(&wh) * self.distribution.g(wo, wi, &wh) * f / (4.0 * cos_theta_i * cos_theta_o)
     }
 
     fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
-        let (wi, pdf) = self.distribution.sample_f(&wo, u1, u2);
-        if same_hemisphere(&wo, &wi) {
-            (self.f(&wo, &wi), pdf)
+        let (wi_sampled, pdf) = self.distribution.sample_f(&wo, u1, u2);
+        *wi = wi_sampled;
+        if same_hemisphere(&wo, wi) {
+            (self.f(&wo, wi), pdf)
         } else {
             (Spectrum::black(), pdf)
         }
@@ -564,18 +1132,383 @@ impl BxDF for Microfacet {
     }
 }
 
+// Ashikhmin-Shirley model of a glossy coating over a diffuse base
+pub struct FresnelBlend {
+    rd: Spectrum,
+    rs: Spectrum,
+    distribution: Box<MicrofacetDistribution>,
+}
+
+impl FresnelBlend {
+    pub fn new(rd: Spectrum, rs: Spectrum, distribution: Box<MicrofacetDistribution>) -> FresnelBlend {
+        FresnelBlend { rd, rs, distribution }
+    }
+
+    fn schlick_fresnel(&self, cos_theta: Float) -> Spectrum {
+        self.rs + (Spectrum::white() - self.rs) * (1.0 - cos_theta).powi(5)
+    }
+}
+
+impl BxDF for FresnelBlend {
+    fn f(&self, wo: &Vector3f, wi: &Vector3f) -> Spectrum {
+        let pow5 = |v: Float| v.powi(5);
+        let diffuse = (28.0 / (23.0 * PI)) * self.rd * (Spectrum::white() - self.rs) *
+            (1.0 - pow5(1.0 - 0.5 * abs_cos_theta(wi))) *
+            (1.0 - pow5(1.0 - 0.5 * abs_cos_theta(wo)));
+
+        let wh = match half_vector(wo, wi) {
+            Some(wh) => wh,
+            None => return Spectrum::black(),
+        };
+        let specular = self.distribution.d(&wh) /
+            (4.0 * wi.dot(wh).abs() * abs_cos_theta(wi).max(abs_cos_theta(wo))) *
+            self.schlick_fresnel(wi.dot(wh));
+
+        diffuse + specular
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        if u1 < 0.5 {
+            // Cosine-sample the hemisphere, flipping the direction if necessary
+            *wi = cosine_sample_hemisphere(2.0 * u1, u2);
+            if wo.z < 0.0 {
+                wi.z *= -1.0;
+            }
+        } else {
+            // Sample reflection from the microfacet distribution
+            *wi = self.distribution.sample_f(&wo, 2.0 * (u1 - 0.5), u2).0;
+            if !same_hemisphere(&wo, wi) {
+                return (Spectrum::black(), 0.0);
+            }
+        }
+        (self.f(&wo, wi), self.pdf(&wo, wi))
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        BxDFType::BSDF_REFLECTION | BxDFType::BSDF_GLOSSY
+    }
+
+    fn pdf(&self, wo: &Vector3f, wi: &Vector3f) -> Float {
+        if !same_hemisphere(wo, wi) {
+            return 0.0;
+        }
+        0.5 * (abs_cos_theta(wi) * INV_PI + self.distribution.pdf(wo, wi))
+    }
+
+    fn rho(&self, wo: &Vector3f) -> Spectrum {
+        // The diffuse term integrates exactly, the glossy term is bounded by its Fresnel reflectance
+        let diffuse = self.rd * (Spectrum::white() - self.rs) * (1.0 - (1.0 - 0.5 * abs_cos_theta(wo)).powi(5));
+        diffuse + self.schlick_fresnel(abs_cos_theta(wo))
+    }
+}
+
+// Rough dielectric transmission after Walter et al. _eta_a_ is the index of refraction on the side
+// of the shading normal and _eta_b_ the one below the surface. As with _SpecularTransmission_,
+// radiance is not scaled by the squared ratio of the indices.
+pub struct MicrofacetTransmission {
+    t: Spectrum,
+    distribution: Box<MicrofacetDistribution>,
+    eta_a: Float,
+    eta_b: Float,
+    fresnel: FresnelDielectric,
+}
+
+impl MicrofacetTransmission {
+    pub fn new(t: Spectrum, distribution: Box<MicrofacetDistribution>, eta_a: Float, eta_b: Float) -> MicrofacetTransmission {
+        MicrofacetTransmission { t, distribution, eta_a, eta_b, fresnel: FresnelDielectric::new(eta_a, eta_b) }
+    }
+
+    // Ratio of the index on the side of _wi_ to the one on the side of _wo_
+    fn eta(&self, wo: &Vector3f) -> Float {
+        if cos_theta(wo) > 0.0 { self.eta_b / self.eta_a } else { self.eta_a / self.eta_b }
+    }
+
+    // Generalized half vector for refraction, oriented to the side of the shading normal
+    fn half_vector(&self, wo: &Vector3f, wi: &Vector3f) -> Option<Vector3f> {
+        let wh = wo + wi * self.eta(wo);
+        if wh.x == 0.0 && wh.y == 0.0 && wh.z == 0.0 {
+            return None;
+        }
+        let wh = wh.normalize();
+        let wh = if wh.z < 0.0 { -wh } else { wh };
+
+        // Discard microfacets facing away from _wo_ or not separating _wo_ and _wi_
+        if wo.dot(wh) * cos_theta(wo) <= 0.0 || wi.dot(wh) * cos_theta(wo) >= 0.0 { None } else { Some(wh) }
+    }
+}
+
+impl BxDF for MicrofacetTransmission {
+    fn f(&self, wo: &Vector3f, wi: &Vector3f) -> Spectrum {
+        if same_hemisphere(wo, wi) {
+            return Spectrum::black();
+        }
+
+        let cos_theta_o = cos_theta(wo);
+        let cos_theta_i = cos_theta(wi);
+        if cos_theta_i == 0.0 || cos_theta_o == 0.0 {
+            return Spectrum::black();
+        }
+
+        let wh = match self.half_vector(wo, wi) {
+            Some(wh) => wh,
+            None => return Spectrum::black(),
+        };
+
+        let f = self.fresnel.evaluate(wo.dot(wh));
+        let sqrt_denom = wo.dot(wh) + self.eta(wo) * wi.dot(wh);
+        (Spectrum::white() - f) * self.t *
+            (self.distribution.d(&wh) * self.distribution.g(wo, wi, &wh) * wi.dot(wh).abs() * wo.dot(wh).abs() /
+                (cos_theta_i * cos_theta_o * sqrt_denom * sqrt_denom)).abs()
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        if wo.z == 0.0 {
+            return (Spectrum::black(), 0.0);
+        }
+        let wh = self.distribution.sample_wh(&wo, u1, u2);
+        if wo.dot(wh) < 0.0 {
+            return (Spectrum::black(), 0.0);
+        }
+
+        // Refract _wo_ through the sampled microfacet
+        match refract(&wo, &wh, 1.0 / self.eta(&wo)) {
+            Some(wt) => {
+                *wi = wt;
+                (self.f(&wo, wi), self.pdf(&wo, wi))
+            }
+            None => (Spectrum::black(), 0.0),
+        }
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        BxDFType::BSDF_TRANSMISSION | BxDFType::BSDF_GLOSSY
+    }
+
+    fn pdf(&self, wo: &Vector3f, wi: &Vector3f) -> Float {
+        if same_hemisphere(wo, wi) {
+            return 0.0;
+        }
+        let wh = match self.half_vector(wo, wi) {
+            Some(wh) => wh,
+            None => return 0.0,
+        };
+
+        // Compute change of variables _dwh_dwi_ for microfacet transmission
+        let eta = self.eta(wo);
+        let sqrt_denom = wo.dot(wh) + eta * wi.dot(wh);
+        let dwh_dwi = (eta * eta * wi.dot(wh) / (sqrt_denom * sqrt_denom)).abs();
+        self.distribution.pdf_wh(wo, &wh) * dwh_dwi
+    }
+}
+
+// Smooth clear coat of relative index _eta_ with absorption coefficient _sigma_a_ and the given
+// _thickness_, shared by the lobes seen through it
+struct Coat {
+    eta: Float,
+    sigma_a: Spectrum,
+    thickness: Float,
+    fresnel: FresnelDielectric,
+}
+
+impl Coat {
+    fn new(eta: Float, sigma_a: Spectrum, thickness: Float) -> Coat {
+        Coat { eta, sigma_a, thickness, fresnel: FresnelDielectric::new(1.0, eta) }
+    }
+
+    // Direction inside the coat refracted from the outside direction _w_, in the upper hemisphere
+    fn inside(&self, w: &Vector3f) -> Vector3f {
+        let (x, y) = (w.x / self.eta, w.y / self.eta);
+        vec3(x, y, (1.0 - x * x - y * y).max(0.0).sqrt())
+    }
+
+    // Outside direction in the upper hemisphere for _w_ inside the coat, unless it is totally reflected
+    fn outside(&self, w: &Vector3f) -> Option<Vector3f> {
+        let (x, y) = (w.x * self.eta, w.y * self.eta);
+        let z2 = 1.0 - x * x - y * y;
+        if z2 <= 0.0 { None } else { Some(vec3(x, y, z2.sqrt())) }
+    }
+
+    // Fresnel transmission into and back out of the coat for the outside directions _wo_ and _wi_
+    fn fresnel_transmission(&self, wo: &Vector3f, wi: &Vector3f) -> Spectrum {
+        (Spectrum::white() - self.fresnel.evaluate(abs_cos_theta(wo))) *
+            (Spectrum::white() - self.fresnel.evaluate(abs_cos_theta(wi)))
+    }
+
+    fn transmittance(&self, wo_inside: &Vector3f, wi_inside: &Vector3f) -> Spectrum {
+        let path = self.thickness * (1.0 / abs_cos_theta(wo_inside) + 1.0 / abs_cos_theta(wi_inside));
+        (-path * self.sigma_a).exp()
+    }
+
+    // Jacobian of the mapping from the outside to the inside incident direction
+    fn dwi_inside(&self, wi: &Vector3f, wi_inside: &Vector3f) -> Float {
+        abs_cos_theta(wi) / (self.eta * self.eta * abs_cos_theta(wi_inside))
+    }
+}
+
+// Non-specular reflection lobes seen through a smooth clear coat of relative index _eta_ with
+// absorption coefficient _sigma_a_ and the given _thickness_. Reflection off the coat itself is not
+// included and specular base lobes belong in _CoatedSpecularBxDF_. Light reflected back down by the
+// coat is accounted for with a geometric series over the diffuse internal Fresnel reflectance.
+pub struct CoatedBxDF {
+    base: Vec<Box<BxDF>>,
+    coat: Coat,
+    scale: Spectrum,
+}
+
+impl CoatedBxDF {
+    pub fn new(base: Vec<Box<BxDF>>, eta: Float, sigma_a: Spectrum, thickness: Float) -> CoatedBxDF {
+        // Keep only non-specular reflection from the base, light transmitted through it is lost
+        let base: Vec<Box<BxDF>> = base.into_iter()
+            .filter(|b| b.bxdf_type().contains(BxDFType::BSDF_REFLECTION) &&
+                !b.bxdf_type().contains(BxDFType::BSDF_SPECULAR))
+            .collect();
+
+        // Compute scale for solid angle compression and interreflection between coat and base
+        let wo = vec3(0.0, 0.0, 1.0);
+        let albedo = base.iter().map(|b| b.rho(&wo)).sum::<Spectrum>().clamp(0.0, 1.0);
+        let average_absorption = (-4.0 * thickness * sigma_a).exp();
+        let internal = Spectrum::from(fresnel_diffuse_reflectance(eta));
+        let scale = Spectrum::white() / ((Spectrum::white() - albedo * average_absorption * internal) * eta * eta);
+
+        CoatedBxDF { base, coat: Coat::new(eta, sigma_a, thickness), scale }
+    }
+}
+
+// Mirror _wo_ and _wi_ to the upper hemisphere, the coat is the same on both sides
+fn upper_hemisphere(wo: &Vector3f, wi: &Vector3f) -> (Vector3f, Vector3f) {
+    if wo.z < 0.0 {
+        (vec3(wo.x, wo.y, -wo.z), vec3(wi.x, wi.y, -wi.z))
+    } else {
+        (*wo, *wi)
+    }
+}
+
+impl BxDF for CoatedBxDF {
+    fn f(&self, wo: &Vector3f, wi: &Vector3f) -> Spectrum {
+        if !same_hemisphere(wo, wi) {
+            return Spectrum::black();
+        }
+        let (wo, wi) = upper_hemisphere(wo, wi);
+        let (wo_inside, wi_inside) = (self.coat.inside(&wo), self.coat.inside(&wi));
+
+        // Attenuate base reflection by Fresnel transmission and absorption in the coat
+        let f: Spectrum = self.base.iter().map(|b| b.f(&wo_inside, &wi_inside)).sum();
+        self.coat.fresnel_transmission(&wo, &wi) * self.coat.transmittance(&wo_inside, &wi_inside) * self.scale * f
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        if self.base.is_empty() || wo.z == 0.0 {
+            return (Spectrum::black(), 0.0);
+        }
+
+        // Cosine-sample half of the directions, sampling the base lobes inside the coat loses
+        // those that are totally reflected when refracted out
+        let wi_up = if u1 < 0.5 {
+            cosine_sample_hemisphere(2.0 * u1, u2)
+        } else {
+            // Choose base lobe and sample it inside the coat
+            let n = self.base.len();
+            let u1 = 2.0 * (u1 - 0.5) * n as Float;
+            let which = (u1 as usize).min(n - 1);
+            let u1 = (u1 - which as Float).min(1.0 - Float::EPSILON);
+            let wo_up = vec3(wo.x, wo.y, wo.z.abs());
+            let mut wi_inside = vec3(0.0, 0.0, 1.0);
+            let (_, pdf) = self.base[which].sample_f(self.coat.inside(&wo_up), &mut wi_inside, u1, u2);
+            if pdf == 0.0 || wi_inside.z <= 0.0 {
+                return (Spectrum::black(), 0.0);
+            }
+
+            // Refract sampled direction out of the coat
+            match self.coat.outside(&wi_inside) {
+                Some(w) => w,
+                None => return (Spectrum::black(), 0.0),
+            }
+        };
+        *wi = if wo.z < 0.0 { vec3(wi_up.x, wi_up.y, -wi_up.z) } else { wi_up };
+        (self.f(&wo, wi), self.pdf(&wo, wi))
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        let glossy = self.base.iter().any(|b| !b.bxdf_type().contains(BxDFType::BSDF_DIFFUSE));
+        BxDFType::BSDF_REFLECTION | if glossy { BxDFType::BSDF_GLOSSY } else { BxDFType::BSDF_DIFFUSE }
+    }
+
+    fn pdf(&self, wo: &Vector3f, wi: &Vector3f) -> Float {
+        if self.base.is_empty() || !same_hemisphere(wo, wi) {
+            return 0.0;
+        }
+        let (wo, wi) = upper_hemisphere(wo, wi);
+        let (wo_inside, wi_inside) = (self.coat.inside(&wo), self.coat.inside(&wi));
+        let pdf: Float = self.base.iter().map(|b| b.pdf(&wo_inside, &wi_inside)).sum();
+        let base_pdf = pdf * self.coat.dwi_inside(&wi, &wi_inside) / self.base.len() as Float;
+        0.5 * (abs_cos_theta(&wi) * INV_PI + base_pdf)
+    }
+}
+
+// Specular reflection lobe _base_ seen through the same clear coat as _CoatedBxDF_. The direction
+// is refracted into the coat, reflected by the base and refracted back out, so the radiance
+// compression cancels. Interreflection between coat and base is not included.
+pub struct CoatedSpecularBxDF {
+    base: Box<BxDF>,
+    coat: Coat,
+}
+
+impl CoatedSpecularBxDF {
+    pub fn new(base: Box<BxDF>, eta: Float, sigma_a: Spectrum, thickness: Float) -> CoatedSpecularBxDF {
+        CoatedSpecularBxDF { base, coat: Coat::new(eta, sigma_a, thickness) }
+    }
+}
+
+impl BxDF for CoatedSpecularBxDF {
+    fn f(&self, _wo: &Vector3f, _wi: &Vector3f) -> Spectrum {
+        Spectrum::black()
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        if wo.z == 0.0 {
+            return (Spectrum::black(), 0.0);
+        }
+
+        // Sample base lobe inside the coat and refract the reflected direction out
+        let wo_up = vec3(wo.x, wo.y, wo.z.abs());
+        let wo_inside = self.coat.inside(&wo_up);
+        let mut wi_inside = vec3(0.0, 0.0, 1.0);
+        let (f, pdf) = self.base.sample_f(wo_inside, &mut wi_inside, u1, u2);
+        if pdf == 0.0 || wi_inside.z <= 0.0 {
+            return (Spectrum::black(), 0.0);
+        }
+        let wi_up = match self.coat.outside(&wi_inside) {
+            Some(w) => w,
+            None => return (Spectrum::black(), 0.0),
+        };
+        *wi = if wo.z < 0.0 { vec3(wi_up.x, wi_up.y, -wi_up.z) } else { wi_up };
+
+        // Carry the base throughput over to the outside direction
+        let t = self.coat.fresnel_transmission(&wo_up, &wi_up) * self.coat.transmittance(&wo_inside, &wi_inside);
+        (t * f * abs_cos_theta(&wi_inside) / abs_cos_theta(&wi_up), pdf)
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        BxDFType::BSDF_REFLECTION | BxDFType::BSDF_SPECULAR
+    }
+
+    fn pdf(&self, _wo: &Vector3f, _wi: &Vector3f) -> Float {
+        0.0
+    }
+}
+
 #[inline]
-fn same_hemisphere(w: &Vector3f, wp: &Vector3f) -> bool {
+pub fn same_hemisphere(w: &Vector3f, wp: &Vector3f) -> bool {
     w.z * wp.z > 0.0
 }
 
 #[inline]
-fn abs_cos_theta(w: &Vector3f) -> Float {
+pub fn abs_cos_theta(w: &Vector3f) -> Float {
     w.z.abs()
 }
 
 #[inline]
-fn cos_theta(w: &Vector3f) -> Float {
+pub fn cos_theta(w: &Vector3f) -> Float {
     w.z
 }
 
@@ -584,3 +1517,63 @@ fn sin_theta2(w: &Vector3f) -> Float {
     (1.0 - cos_theta(w) * cos_theta(w)).max(0.0)
 }
 
+#[inline]
+fn sin_theta(w: &Vector3f) -> Float {
+    sin_theta2(w).sqrt()
+}
+
+#[inline]
+fn tan_theta2(w: &Vector3f) -> Float {
+    sin_theta2(w) / (cos_theta(w) * cos_theta(w))
+}
+
+#[inline]
+fn cos2_phi(w: &Vector3f) -> Float {
+    cos_phi(w) * cos_phi(w)
+}
+
+#[inline]
+fn sin2_phi(w: &Vector3f) -> Float {
+    sin_phi(w) * sin_phi(w)
+}
+
+#[inline]
+pub fn reflect(wo: &Vector3f, n: &Vector3f) -> Vector3f {
+    -*wo + 2.0 * wo.dot(*n) * n
+}
+
+// Refract _wi_ about _n_ given the ratio _eta_ of the incident to the transmitted index
+#[inline]
+fn refract(wi: &Vector3f, n: &Vector3f, eta: Float) -> Option<Vector3f> {
+    // Compute $\cos \theta_\roman{t}$ using Snell's law
+    let cos_theta_i = n.dot(*wi);
+    let sin2_theta_i = (1.0 - cos_theta_i * cos_theta_i).max(0.0);
+    let sin2_theta_t = eta * eta * sin2_theta_i;
+
+    // Handle total internal reflection for transmission
+    if sin2_theta_t >= 1.0 {
+        return None;
+    }
+    let cos_theta_t = (1.0 - sin2_theta_t).sqrt();
+    Some(eta * -*wi + (eta * cos_theta_i - cos_theta_t) * n)
+}
+
+// Normalized half vector of _wo_ and _wi_, if they are not opposite
+#[inline]
+pub fn half_vector(wo: &Vector3f, wi: &Vector3f) -> Option<Vector3f> {
+    let wh = wo + wi;
+    if wh.x == 0.0 && wh.y == 0.0 && wh.z == 0.0 { None } else { Some(wh.normalize()) }
+}
+
+#[inline]
+fn cos_phi(w: &Vector3f) -> Float {
+    let sintheta = sin_theta(w);
+    if sintheta == 0.0 { 1.0 } else { clamp(w.x / sintheta, -1.0, 1.0) }
+}
+
+#[inline]
+fn sin_phi(w: &Vector3f) -> Float {
+    let sintheta = sin_theta(w);
+    if sintheta == 0.0 { 0.0 } else { clamp(w.y / sintheta, -1.0, 1.0) }
+}
+
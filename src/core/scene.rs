@@ -1,18 +1,25 @@
 use core::{
-    geometry::Ray,
+    geometry::{Ray, BBox},
     intersection::Intersection,
     light::Light,
     primitive::Primitive,
 };
+use std::sync::Arc;
 
 pub struct Scene {
     aggregate: Box<Primitive>,
-    pub lights: Vec<Box<Light>>,
+    pub lights: Vec<Arc<Light>>,
+    bound: BBox,
 }
 
 impl Scene {
-    pub fn new(aggregate: Box<Primitive>, lights: Vec<Box<Light>>) -> Scene {
-        Scene { aggregate, lights }
+    pub fn new(aggregate: Box<Primitive>, lights: Vec<Arc<Light>>) -> Scene {
+        let bound = aggregate.world_bound();
+        Scene { aggregate, lights, bound }
+    }
+
+    pub fn world_bound(&self) -> &BBox {
+        &self.bound
     }
 
     pub fn intersect(&self, ray: &mut Ray) -> Option<Intersection> {
@@ -1,7 +1,7 @@
 use core::{
     differential_geometry::DifferentialGeometry,
-    geometry::Ray,
-    types::Float,
+    geometry::{Ray, distance_squared},
+    types::{Float, INFINITY},
     transform::Transform,
 };
 use std::fmt::Debug;
@@ -9,6 +9,8 @@ use std::sync::Arc;
 use core::geometry::Vector3f;
 use core::geometry::Point3f;
 use core::geometry::Normal;
+use core::geometry::BBox;
+use cgmath::prelude::*;
 
 pub trait Shape : Debug {
     fn intersect(&self, ray: &Ray) -> Option<(DifferentialGeometry, Float, Float)>;
@@ -21,6 +23,12 @@ pub trait Shape : Debug {
 
     fn get_object_to_world(&self) -> &Transform;
 
+    fn object_bound(&self) -> BBox;
+
+    fn world_bound(&self) -> BBox {
+        self.get_object_to_world().transform_bbox(&self.object_bound())
+    }
+
     fn get_shading_geometry<'a, 'b>(&'a self, _obj_to_world: &'b Transform, dg: &'a DifferentialGeometry) -> &'a DifferentialGeometry {
         dg
     }
@@ -47,3 +55,16 @@ pub trait Shape : Debug {
 
     fn transform_swaps_handedness(&self) -> bool;
 }
+
+// Solid angle density of uniformly sampling the area of _shape_ as seen from _p_
+pub fn shape_pdf(shape: &Shape, p: &Point3f, wi: &Vector3f) -> Float {
+    // Intersect sample ray with area light geometry
+    let ray = Ray::new(*p, *wi, 1e-3, INFINITY, 0.0);
+    if let Some((dg_light, thit, _)) = shape.intersect(&ray) {
+        // Convert light sample weight to solid angle measure
+        let pdf = distance_squared(p, &ray.point_at(thit)) / (dg_light.nn.v.dot(-*wi).abs() * shape.area());
+        if pdf.is_infinite() { 0.0 } else { pdf }
+    } else {
+        0.0
+    }
+}
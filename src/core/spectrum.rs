@@ -21,6 +21,39 @@ impl Spectrum {
         Spectrum { r, g, b }
     }
 
+    // Convert CIE XYZ to linear sRGB with the D65 matrix as published
+    #[allow(clippy::excessive_precision)]
+    pub fn from_xyz(x: Float, y: Float, z: Float) -> Spectrum {
+        Spectrum::new(3.240479 * x - 1.537150 * y - 0.498535 * z,
+                      -0.969256 * x + 1.875991 * y + 0.041556 * z,
+                      0.055648 * x - 0.204043 * y + 1.057311 * z)
+    }
+
+    // Color of a blackbody emitter at _temperature_ Kelvin, normalized to unit luminance
+    #[allow(clippy::excessive_precision)]
+    pub fn blackbody(temperature: Float) -> Spectrum {
+        // Approximate chromaticity of the Planckian locus, coefficients as published by Kang et al. 2002
+        let t = clamp(temperature, 1667.0, 25000.0);
+        let (t2, t3) = (t * t, t * t * t);
+        let x = if t <= 4000.0 {
+            -0.2661239e9 / t3 - 0.2343589e6 / t2 + 0.8776956e3 / t + 0.179910
+        } else {
+            -3.0258469e9 / t3 + 2.1070379e6 / t2 + 0.2226347e3 / t + 0.240390
+        };
+        let (x2, x3) = (x * x, x * x * x);
+        let y = if t <= 2222.0 {
+            -1.1063814 * x3 - 1.34811020 * x2 + 2.18555832 * x - 0.20219683
+        } else if t <= 4000.0 {
+            -0.9549476 * x3 - 1.37418593 * x2 + 2.09137015 * x - 0.16748867
+        } else {
+            3.0817580 * x3 - 5.87338670 * x2 + 3.75112997 * x - 0.37001483
+        };
+
+        // Convert $xyY$ with unit luminance to RGB
+        let c = Spectrum::from_xyz(x / y, 1.0, (1.0 - x - y) / y).clamp_positive();
+        c / c.y()
+    }
+
     pub fn black() -> Spectrum {
         Spectrum::new(0.0, 0.0, 0.0)
     }
@@ -50,6 +83,10 @@ impl Spectrum {
         }
     }
 
+    pub fn exp(&self) -> Spectrum {
+        Spectrum::new(self.r.exp(), self.g.exp(), self.b.exp())
+    }
+
     pub fn y(&self) -> Float {
         let yweight = [0.212671, 0.715160, 0.072169];
         return yweight[0] * self.r + yweight[1] * self.g + yweight[2] * self.b;
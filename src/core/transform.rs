@@ -1,8 +1,8 @@
 use core::{
-    geometry::{Point3f, Normal, Vector3f, Ray},
+    geometry::{Point3f, Normal, Vector3f, Ray, BBox},
     types::Float,
 };
-use cgmath::{Matrix4, SquareMatrix, Transform as TransformCG, prelude::*};
+use cgmath::{Matrix4, Rad, SquareMatrix, Transform as TransformCG, prelude::*};
 use core::math::radians;
 use std::ops::Mul;
 
@@ -50,6 +50,17 @@ impl Transform {
         Ray::new(self.transform_point(ray.o), self.transform_vector(ray.d), ray.mint, ray.maxt, ray.time)
     }
 
+    pub fn transform_bbox(&self, b: &BBox) -> BBox {
+        let mut ret = BBox::from_point(self.transform_point(b.p_min));
+        for i in 1..8 {
+            let corner = Point3f::new(if i & 1 == 0 { b.p_min.x } else { b.p_max.x },
+                                      if i & 2 == 0 { b.p_min.y } else { b.p_max.y },
+                                      if i & 4 == 0 { b.p_min.z } else { b.p_max.z });
+            ret = ret.union_point(&self.transform_point(corner));
+        }
+        ret
+    }
+
     pub fn invert(&self) -> Transform {
         Transform { m: self.m_inv, m_inv: self.m }
     }
@@ -100,6 +111,21 @@ pub fn translate(delta: &Vector3f) -> Transform {
     }
 }
 
+pub fn rotate_x(angle: Float) -> Transform {
+    let m = Matrix4::from_angle_x(Rad(radians(angle)));
+    Transform { m, m_inv: m.transpose() }
+}
+
+pub fn rotate_y(angle: Float) -> Transform {
+    let m = Matrix4::from_angle_y(Rad(radians(angle)));
+    Transform { m, m_inv: m.transpose() }
+}
+
+pub fn rotate_z(angle: Float) -> Transform {
+    let m = Matrix4::from_angle_z(Rad(radians(angle)));
+    Transform { m, m_inv: m.transpose() }
+}
+
 pub fn perspective(fov: Float, n: Float, f: Float) -> Transform {
     // Perform projective divide
     let persp = Matrix4::new(1.0, 0.0, 0.0, 0.0,
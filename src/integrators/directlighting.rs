@@ -1,8 +1,9 @@
 use core::{
     geometry::RayDifferential,
-    integrator::{Integrator, SurfaceIntegrator,specular_reflect,specular_transmit, uniform_sample_one_light, uniform_sample_all_lights},
+    integrator::{Integrator, SurfaceIntegrator,specular_reflect,specular_transmit, sample_one_light, uniform_sample_all_lights},
     intersection::Intersection,
     light::LightSampleOffsets,
+    lightdistrib::{LightDistribution, LightSampleStrategy, create_light_distribution},
     reflection::BSDFSampleOffsets,
     renderer::Renderer,
     rng::RNG,
@@ -11,7 +12,8 @@ use core::{
     spectrum::Spectrum,
 };
 
-pub enum LightStrategy { SampleAllUniform, SampleOneUniform }
+#[derive(Clone, Copy)]
+pub enum LightStrategy { SampleAllUniform, SampleOne(LightSampleStrategy) }
 
 pub struct DirectLightingIntegrator {
     strategy: LightStrategy,
@@ -20,6 +22,7 @@ pub struct DirectLightingIntegrator {
     light_sample_offsets: Vec<LightSampleOffsets>,
     bsdf_sample_offsets: Vec<BSDFSampleOffsets>,
     light_num_offset: Option<SampleOffset1d>,
+    light_distribution: Option<Box<LightDistribution>>,
 }
 
 impl DirectLightingIntegrator {
@@ -30,6 +33,7 @@ impl DirectLightingIntegrator {
             light_sample_offsets: vec![],
             bsdf_sample_offsets: vec![],
             light_num_offset: None,
+            light_distribution: None,
         }
     }
 }
@@ -60,10 +64,11 @@ impl SurfaceIntegrator for DirectLightingIntegrator {
                     uniform_sample_all_lights(scene, renderer, &p, &n, &wo,
                                            isect.ray_epsilon, rd.ray.time, &bsdf, sample, rng,
                                            Some(&self.light_sample_offsets), Some(&self.bsdf_sample_offsets)),
-                LightStrategy::SampleOneUniform =>
-                    uniform_sample_one_light(scene, renderer, &p, &n, &wo,
-                                          isect.ray_epsilon, rd.ray.time, &bsdf, sample, rng,
-                                          self.light_num_offset, Some(&self.light_sample_offsets[0]), Some(&self.bsdf_sample_offsets[0]))
+                LightStrategy::SampleOne(_) =>
+                    sample_one_light(scene, renderer, &p, &n, &wo,
+                                     isect.ray_epsilon, rd.ray.time, &bsdf, sample, rng,
+                                     self.light_distribution.as_ref().expect("light distribution not initialized").as_ref(),
+                                     self.light_num_offset, Some(&self.light_sample_offsets[0]), Some(&self.bsdf_sample_offsets[0]))
             }
         }
 
@@ -97,8 +102,9 @@ impl Integrator for DirectLightingIntegrator {
                 }
                 self.light_num_offset = None;
             }
-            LightStrategy::SampleOneUniform => {
+            LightStrategy::SampleOne(light_strategy) => {
                 // Allocate and request samples for sampling one light
+                self.light_distribution = Some(create_light_distribution(light_strategy, scene));
                 self.light_sample_offsets.push(LightSampleOffsets::new(1, sample));
                 self.light_num_offset = Some(sample.add_1d(1));
                 self.bsdf_sample_offsets.push(BSDFSampleOffsets::new(1, sample));
@@ -1,8 +1,9 @@
 use core::{
     geometry::RayDifferential,
-    integrator::{Integrator, SurfaceIntegrator, uniform_sample_one_light},
+    integrator::{Integrator, SurfaceIntegrator, sample_one_light},
     intersection::Intersection,
     light::LightSampleOffsets,
+    lightdistrib::{LightDistribution, LightSampleStrategy, create_light_distribution},
     reflection::{BxDFType, BSDFSampleOffsets, BSDFSample},
     renderer::Renderer,
     rng::RNG,
@@ -18,6 +19,8 @@ const SAMPLE_DEPTH: usize = 3;
 
 pub struct PathIntegrator {
     max_depth: usize,
+    light_strategy: LightSampleStrategy,
+    light_distribution: Option<Box<LightDistribution>>,
     light_sample_offsets: Vec<LightSampleOffsets>,
     light_num_offset: Vec<SampleOffset1d>,
     bsdf_sample_offsets: Vec<BSDFSampleOffsets>,
@@ -25,9 +28,11 @@ pub struct PathIntegrator {
 }
 
 impl PathIntegrator {
-    pub fn new(max_depth: usize) -> PathIntegrator {
+    pub fn new(max_depth: usize, light_strategy: LightSampleStrategy) -> PathIntegrator {
         PathIntegrator {
             max_depth,
+            light_strategy,
+            light_distribution: None,
             light_sample_offsets: Vec::with_capacity(SAMPLE_DEPTH),
             light_num_offset: Vec::with_capacity(SAMPLE_DEPTH),
             bsdf_sample_offsets: Vec::with_capacity(SAMPLE_DEPTH),
@@ -38,7 +43,7 @@ impl PathIntegrator {
 
 impl Default for PathIntegrator {
     fn default() -> Self {
-        PathIntegrator::new(5)
+        PathIntegrator::new(5, LightSampleStrategy::Power)
     }
 }
 
@@ -50,6 +55,7 @@ impl SurfaceIntegrator for PathIntegrator {
         let mut ray: RayDifferential = r.clone();
         let mut specular_bounce = false;
         let mut isectp = isect.clone();
+        let light_distribution = self.light_distribution.as_ref().expect("light distribution not initialized").as_ref();
 
         for bounces in 0..usize::MAX {
             // Possibly add emitted light at path vertex
@@ -64,15 +70,15 @@ impl SurfaceIntegrator for PathIntegrator {
                 let wo = -ray.ray.d;
                 if bounces < SAMPLE_DEPTH {
                     L += path_throughput *
-                        uniform_sample_one_light(scene, renderer, &p, &n, &wo, isectp.ray_epsilon, ray.ray.time,
-                                                 &bsdf, sample, rng,
-                                                 Some(self.light_num_offset[bounces]),
-                                                 Some(&self.light_sample_offsets[bounces]),
-                                                 Some(&self.bsdf_sample_offsets[bounces]));
+                        sample_one_light(scene, renderer, &p, &n, &wo, isectp.ray_epsilon, ray.ray.time,
+                                         &bsdf, sample, rng, light_distribution,
+                                         Some(self.light_num_offset[bounces]),
+                                         Some(&self.light_sample_offsets[bounces]),
+                                         Some(&self.bsdf_sample_offsets[bounces]));
                 } else {
                     L += path_throughput *
-                        uniform_sample_one_light(scene, renderer, &p, &n, &wo, isectp.ray_epsilon, ray.ray.time,
-                                                 &bsdf, sample, rng, None, None, None);
+                        sample_one_light(scene, renderer, &p, &n, &wo, isectp.ray_epsilon, ray.ray.time,
+                                         &bsdf, sample, rng, light_distribution, None, None, None);
                 }
 
                 // Sample BSDF to get new path direction
@@ -125,7 +131,8 @@ impl SurfaceIntegrator for PathIntegrator {
 }
 
 impl Integrator for PathIntegrator {
-    fn request_samples(&mut self, _sampler: Option<&Sampler>, sample: &mut Sample, _scene: &Scene) {
+    fn request_samples(&mut self, _sampler: Option<&Sampler>, sample: &mut Sample, scene: &Scene) {
+        self.light_distribution = Some(create_light_distribution(self.light_strategy, scene));
         for i in 0..SAMPLE_DEPTH {
             self.light_sample_offsets.push(LightSampleOffsets::new(1, sample));
             self.light_num_offset.push(sample.add_1d(1));
@@ -1,18 +1,26 @@
 use core::{
-    geometry::{Normal, Vector3f, Point3f},
+    differential_geometry::DifferentialGeometry,
+    geometry::{Vector3f, Point3f, BBox},
     light::{Light, AreaLight, ShapeSet, LightSample, VisibilityTester},
     scene::Scene,
     shape::Shape,
     spectrum::Spectrum,
+    texture::Texture,
     transform::Transform,
     types::{Float,PI},
 };
+use textures::ConstantTexture;
 use std::sync::Arc;
 use cgmath::prelude::*;
 
+// Number of surface samples used to estimate the average of textured emission
+const POWER_ESTIMATE_SAMPLES: usize = 16;
+
 pub struct DiffuseAreaLight {
     light_to_world: Transform,
-    l_emit: Spectrum,
+    l_emit: Arc<Texture<Spectrum>>,
+    scale: Spectrum,
+    two_sided: bool,
     shape_set: ShapeSet,
     area: Float,
     num_samples: u32
@@ -20,22 +28,54 @@ pub struct DiffuseAreaLight {
 
 impl DiffuseAreaLight {
     pub fn new(light_to_world: Transform, l_emit: Spectrum, num_samples: u32, shape: Arc<Shape>) -> DiffuseAreaLight {
+        DiffuseAreaLight::new_textured(light_to_world, Arc::new(ConstantTexture::new(l_emit)), 1.0, None, false, num_samples, shape)
+    }
+
+    // Emission is _l_emit_ evaluated at the surface, scaled by _scale_ and tinted by the
+    // normalized color of a blackbody at _temperature_ Kelvin when given
+    pub fn new_textured(light_to_world: Transform,
+                        l_emit: Arc<Texture<Spectrum>>,
+                        scale: Float,
+                        temperature: Option<Float>,
+                        two_sided: bool,
+                        num_samples: u32,
+                        shape: Arc<Shape>) -> DiffuseAreaLight {
         let shape_set = ShapeSet::new(shape);
         let area = shape_set.area();
-        DiffuseAreaLight { light_to_world, l_emit, shape_set, area, num_samples }
+        let scale = temperature.map_or(Spectrum::from(scale), |t| scale * Spectrum::blackbody(t));
+        DiffuseAreaLight { light_to_world, l_emit, scale, two_sided, shape_set, area, num_samples }
+    }
+
+    // Estimate average emitted radiance by evaluating the texture at stratified surface points
+    fn average_emission(&self) -> Spectrum {
+        let mut sum = Spectrum::black();
+        let mut count = 0;
+        let n = POWER_ESTIMATE_SAMPLES;
+        for i in 0..n * n {
+            let (x, y) = (i % n, i / n);
+            let ls = LightSample {
+                u_component: (i as Float + 0.5) / (n * n) as Float,
+                u_pos: [(x as Float + 0.5) / n as Float, (y as Float + 0.5) / n as Float],
+            };
+            if let Some(dg) = self.shape_set.sample_geometry(&ls) {
+                sum += self.l_emit.evaluate(&dg);
+                count += 1;
+            }
+        }
+        if count == 0 { Spectrum::black() } else { sum / count as Float }
     }
 }
 
 impl Light for DiffuseAreaLight {
     fn sample_l(&self, p: &Point3f, p_epsilon: Float, ls: &LightSample, time: Float, visibility: &mut VisibilityTester) -> (Spectrum, Vector3f, Float) {
-        let (ps, ns) = self.shape_set.sample_point(&p, &ls);
+        let dg = self.shape_set.sample_point(p, ls);
 
-        let wi = (ps - p).normalize();
+        let wi = (dg.p - p).normalize();
         let pdf = self.shape_set.pdf(p, &wi);
 
-        visibility.set_segment(*p, p_epsilon, ps, 1e-3, time);
+        visibility.set_segment(*p, p_epsilon, dg.p, 1e-3, time);
 
-        let ls = self.l(&ps, &ns, &-wi);
+        let ls = self.l(&dg, &-wi);
 
         (ls, wi, pdf)
     }
@@ -44,8 +84,9 @@ impl Light for DiffuseAreaLight {
         self.shape_set.pdf(p, wi)
     }
 
-    fn power(&self, scene: &Scene) -> Spectrum {
-        self.l_emit * self.area * PI
+    fn power(&self, _scene: &Scene) -> Spectrum {
+        let sides = if self.two_sided { 2.0 } else { 1.0 };
+        self.scale * self.average_emission() * self.area * PI * sides
     }
 
     fn num_samples(&self) -> u32 {
@@ -55,12 +96,16 @@ impl Light for DiffuseAreaLight {
     fn is_delta_light(&self) -> bool {
         false
     }
+
+    fn bound(&self) -> Option<BBox> {
+        Some(self.shape_set.world_bound())
+    }
 }
 
 impl AreaLight for DiffuseAreaLight {
-    fn l(&self, p: &Point3f, n: &Normal, w: &Vector3f) -> Spectrum {
-        if n.v.dot(*w) > 0.0 {
-            self.l_emit
+    fn l(&self, dg: &DifferentialGeometry, w: &Vector3f) -> Spectrum {
+        if self.two_sided || dg.nn.v.dot(*w) > 0.0 {
+            self.scale * self.l_emit.evaluate(dg)
         } else {
             Spectrum::black()
         }
@@ -0,0 +1,48 @@
+use core::light::Light;
+use core::spectrum::Spectrum;
+use core::geometry::Point3f;
+use core::light::LightSample;
+use core::light::VisibilityTester;
+use core::geometry::Vector3f;
+use core::types::{PI, Float};
+use core::transform::Transform;
+use cgmath::prelude::*;
+use core::scene::Scene;
+
+pub struct DistantLight {
+    light_dir: Vector3f,
+    radiance: Spectrum,
+}
+
+impl DistantLight {
+    pub fn new(light_to_world: &Transform, radiance: Spectrum, dir: Vector3f) -> DistantLight {
+        DistantLight {
+            light_dir: light_to_world.transform_vector(dir).normalize(),
+            radiance,
+        }
+    }
+}
+
+impl Light for DistantLight {
+    fn sample_l(&self, p: &Point3f, p_epsilon: Float, _: &LightSample, time: Float, visibility: &mut VisibilityTester) -> (Spectrum, Vector3f, Float) {
+        visibility.set_ray(*p, p_epsilon, self.light_dir, time);
+        (self.radiance, self.light_dir, 1.0)
+    }
+
+    fn pdf(&self, _p: &Point3f, _wi: &Vector3f) -> Float {
+        0.0
+    }
+
+    fn power(&self, scene: &Scene) -> Spectrum {
+        let (_, world_radius) = scene.world_bound().bounding_sphere();
+        self.radiance * PI * world_radius * world_radius
+    }
+
+    fn num_samples(&self) -> u32 {
+        1
+    }
+
+    fn is_delta_light(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,75 @@
+use core::light::Light;
+use core::spectrum::Spectrum;
+use core::geometry::{Point3f, BBox};
+use core::light::LightSample;
+use core::light::VisibilityTester;
+use core::geometry::Vector3f;
+use core::types::Float;
+use core::geometry::distance_squared;
+use core::transform::Transform;
+use core::math::{clamp, degrees};
+use core::photometry::{PhotometricData, LUMINOUS_EFFICACY};
+use cgmath::prelude::*;
+use core::scene::Scene;
+
+// Point light with a measured intensity distribution. In light space the photometric nadir
+// is $-y$ and horizontal angles are measured around $y$ from $+x$ towards $+z$.
+pub struct GoniometricLight {
+    world_to_light: Transform,
+    pos: Point3f,
+    intensity: Spectrum,
+    data: PhotometricData,
+    flux: Float,
+}
+
+impl GoniometricLight {
+    // Candela values are scaled by _intensity_ and converted to radiometric units
+    pub fn new(light_to_world: Transform, intensity: Spectrum, data: PhotometricData) -> GoniometricLight {
+        let world_to_light = light_to_world.invert();
+        let pos = light_to_world.transform_point(Point3f::new(0.0, 0.0, 0.0));
+        let flux = data.luminous_flux();
+        GoniometricLight {
+            world_to_light,
+            pos,
+            intensity: intensity / LUMINOUS_EFFICACY,
+            data,
+            flux,
+        }
+    }
+
+    fn scale(&self, w: &Vector3f) -> Float {
+        let wl = self.world_to_light.transform_vector(*w).normalize();
+        let vertical = degrees(clamp(-wl.y, -1.0, 1.0).acos());
+        let horizontal = degrees(wl.z.atan2(wl.x));
+        self.data.intensity(vertical, horizontal)
+    }
+}
+
+impl Light for GoniometricLight {
+    fn sample_l(&self, p: &Point3f, p_epsilon: Float, _: &LightSample, time: Float, visibility: &mut VisibilityTester) -> (Spectrum, Vector3f, Float) {
+        let wi = (self.pos - p).normalize();
+        visibility.set_segment(*p, p_epsilon, self.pos, 0.0, time);
+        let c = self.intensity * self.scale(&-wi) / distance_squared(&self.pos, p);
+        (c, wi, 1.0)
+    }
+
+    fn pdf(&self, _p: &Point3f, _wi: &Vector3f) -> Float {
+        0.0
+    }
+
+    fn power(&self, _scene: &Scene) -> Spectrum {
+        self.intensity * self.flux
+    }
+
+    fn num_samples(&self) -> u32 {
+        1
+    }
+
+    fn is_delta_light(&self) -> bool {
+        true
+    }
+
+    fn bound(&self) -> Option<BBox> {
+        Some(BBox::from_point(self.pos))
+    }
+}
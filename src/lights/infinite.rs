@@ -0,0 +1,105 @@
+use core::{
+    geometry::{Point3f, Vector3f, RayDifferential, spherical_theta, spherical_phi},
+    imageio::{RGBImage, ImageWrap},
+    light::{Light, LightSample, VisibilityTester},
+    montecarlo::Distribution2D,
+    scene::Scene,
+    spectrum::Spectrum,
+    transform::Transform,
+    types::{Float, PI, INV_PI, INV_TWO_PI},
+};
+use cgmath::{vec3, prelude::*};
+
+pub struct InfiniteAreaLight {
+    light_to_world: Transform,
+    world_to_light: Transform,
+    radiance_map: RGBImage,
+    distribution: Distribution2D,
+    num_samples: u32,
+}
+
+impl InfiniteAreaLight {
+    // _radiance_map_ is an equirectangular image with $\theta$ measured from the light-space $z$ axis
+    pub fn new(light_to_world: Transform, l: Spectrum, num_samples: u32, radiance_map: RGBImage) -> InfiniteAreaLight {
+        let radiance_map = radiance_map.map(|t| t * l);
+
+        // Compute scalar-valued image _img_ from environment map
+        let width = radiance_map.width() as usize;
+        let height = radiance_map.height() as usize;
+        let mut img = Vec::with_capacity(width * height);
+        for v in 0..height {
+            let vp = (v as Float + 0.5) / height as Float;
+            let sin_theta = (PI * vp).sin();
+            for u in 0..width {
+                let up = (u as Float + 0.5) / width as Float;
+                img.push(radiance_map.lookup(up, vp, ImageWrap::Repeat).y() * sin_theta);
+            }
+        }
+
+        // Compute sampling distributions for rows and columns of image
+        let distribution = Distribution2D::new(&img, width, height);
+
+        InfiniteAreaLight {
+            world_to_light: light_to_world.invert(),
+            light_to_world,
+            radiance_map,
+            distribution,
+            num_samples,
+        }
+    }
+}
+
+impl Light for InfiniteAreaLight {
+    fn sample_l(&self, p: &Point3f, p_epsilon: Float, ls: &LightSample, time: Float, visibility: &mut VisibilityTester) -> (Spectrum, Vector3f, Float) {
+        // Find $(u,v)$ sample coordinates in infinite light texture
+        let (uv, map_pdf) = self.distribution.sample_continuous(ls.u_pos[0], ls.u_pos[1]);
+        if map_pdf == 0.0 {
+            return (Spectrum::black(), Vector3f::unit_z(), 0.0);
+        }
+
+        // Convert infinite light sample point to direction
+        let theta = uv[1] * PI;
+        let phi = uv[0] * 2.0 * PI;
+        let (sintheta, costheta) = theta.sin_cos();
+        let (sinphi, cosphi) = phi.sin_cos();
+        let wi = self.light_to_world.transform_vector(vec3(sintheta * cosphi, sintheta * sinphi, costheta));
+
+        // Compute PDF for sampled infinite light direction
+        let pdf = if sintheta == 0.0 { 0.0 } else { map_pdf / (2.0 * PI * PI * sintheta) };
+
+        // Return radiance value for infinite light direction
+        visibility.set_ray(*p, p_epsilon, wi, time);
+        (self.radiance_map.lookup(uv[0], uv[1], ImageWrap::Repeat), wi, pdf)
+    }
+
+    fn le(&self, ray: &RayDifferential) -> Spectrum {
+        let wh = self.world_to_light.transform_vector(ray.ray.d).normalize();
+        let s = spherical_phi(&wh) * INV_TWO_PI;
+        let t = spherical_theta(&wh) * INV_PI;
+        self.radiance_map.lookup(s, t, ImageWrap::Repeat)
+    }
+
+    fn pdf(&self, _p: &Point3f, w: &Vector3f) -> Float {
+        let wi = self.world_to_light.transform_vector(*w).normalize();
+        let theta = spherical_theta(&wi);
+        let phi = spherical_phi(&wi);
+        let sintheta = theta.sin();
+        if sintheta == 0.0 {
+            return 0.0;
+        }
+        self.distribution.pdf(phi * INV_TWO_PI, theta * INV_PI) / (2.0 * PI * PI * sintheta)
+    }
+
+    fn power(&self, scene: &Scene) -> Spectrum {
+        let (_, world_radius) = scene.world_bound().bounding_sphere();
+        PI * world_radius * world_radius * self.radiance_map.average()
+    }
+
+    fn num_samples(&self) -> u32 {
+        self.num_samples
+    }
+
+    fn is_delta_light(&self) -> bool {
+        false
+    }
+}
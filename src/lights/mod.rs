@@ -1,5 +1,17 @@
 mod diffuse;
+mod distant;
+mod goniometric;
+mod infinite;
 mod point;
+mod projection;
+mod sky;
+mod spot;
 
 pub use self::diffuse::DiffuseAreaLight;
+pub use self::distant::DistantLight;
+pub use self::goniometric::GoniometricLight;
+pub use self::infinite::InfiniteAreaLight;
 pub use self::point::PointLight;
+pub use self::projection::ProjectionLight;
+pub use self::sky::{SkyModel, SkyLight, SunLight};
+pub use self::spot::SpotLight;
@@ -1,6 +1,6 @@
 use core::light::Light;
 use core::spectrum::Spectrum;
-use core::geometry::Point3f;
+use core::geometry::{Point3f, BBox};
 use core::light::LightSample;
 use core::light::VisibilityTester;
 use core::geometry::Vector3f;
@@ -43,4 +43,8 @@ impl Light for PointLight {
     fn is_delta_light(&self) -> bool {
         true
     }
+
+    fn bound(&self) -> Option<BBox> {
+        Some(BBox::from_point(self.pos))
+    }
 }
@@ -0,0 +1,106 @@
+use core::light::Light;
+use core::spectrum::Spectrum;
+use core::geometry::{Point3f, BBox};
+use core::light::LightSample;
+use core::light::VisibilityTester;
+use core::geometry::Vector3f;
+use core::types::{PI, Float};
+use core::geometry::distance_squared;
+use core::imageio::{ImageWrap, RGBImage};
+use core::transform::{Transform, perspective};
+use core::math::radians;
+use cgmath::prelude::*;
+use core::scene::Scene;
+
+pub struct ProjectionLight {
+    world_to_light: Transform,
+    light_projection: Transform,
+    pos: Point3f,
+    intensity: Spectrum,
+    projection_map: RGBImage,
+    screen_window: [Float; 4],
+    hither: Float,
+    cos_total_width: Float,
+}
+
+impl ProjectionLight {
+    pub fn new(light_to_world: Transform, intensity: Spectrum, projection_map: RGBImage, fov: Float) -> ProjectionLight {
+        let world_to_light = light_to_world.invert();
+        let pos = light_to_world.transform_point(Point3f::new(0.0, 0.0, 0.0));
+
+        // Initialize projection light screen window from image aspect ratio
+        let aspect = projection_map.width() as Float / projection_map.height() as Float;
+        let screen_window = if aspect > 1.0 {
+            [-aspect, aspect, -1.0, 1.0]
+        } else {
+            [-1.0, 1.0, -1.0 / aspect, 1.0 / aspect]
+        };
+        let hither = 1e-3;
+        let yon = 1e30;
+        let light_projection = perspective(fov, hither, yon);
+
+        // Compute cosine of cone surrounding projection directions
+        let opposite = (radians(fov) / 2.0).tan();
+        let tan_diag = opposite * (1.0 + 1.0 / (aspect * aspect)).sqrt();
+        let cos_total_width = tan_diag.atan().cos();
+
+        ProjectionLight {
+            world_to_light,
+            light_projection,
+            pos,
+            intensity,
+            projection_map,
+            screen_window,
+            hither,
+            cos_total_width,
+        }
+    }
+
+    fn projection(&self, w: &Vector3f) -> Spectrum {
+        let wl = self.world_to_light.transform_vector(*w);
+
+        // Discard directions behind projection light
+        if wl.z < self.hither {
+            return Spectrum::black();
+        }
+
+        // Project point onto projection plane and compute light
+        let pl = self.light_projection.transform_point(Point3f::new(wl.x, wl.y, wl.z));
+        let [x0, x1, y0, y1] = self.screen_window;
+        if pl.x < x0 || pl.x > x1 || pl.y < y0 || pl.y > y1 {
+            return Spectrum::black();
+        }
+        let s = (pl.x - x0) / (x1 - x0);
+        let t = (y1 - pl.y) / (y1 - y0);
+        self.projection_map.lookup(s, t, ImageWrap::Clamp)
+    }
+}
+
+impl Light for ProjectionLight {
+    fn sample_l(&self, p: &Point3f, p_epsilon: Float, _: &LightSample, time: Float, visibility: &mut VisibilityTester) -> (Spectrum, Vector3f, Float) {
+        let wi = (self.pos - p).normalize();
+        visibility.set_segment(*p, p_epsilon, self.pos, 0.0, time);
+        let c = self.intensity * self.projection(&-wi) / distance_squared(&self.pos, p);
+        (c, wi, 1.0)
+    }
+
+    fn pdf(&self, _p: &Point3f, _wi: &Vector3f) -> Float {
+        0.0
+    }
+
+    fn power(&self, _scene: &Scene) -> Spectrum {
+        self.intensity * 2.0 * PI * (1.0 - self.cos_total_width) * self.projection_map.average()
+    }
+
+    fn num_samples(&self) -> u32 {
+        1
+    }
+
+    fn is_delta_light(&self) -> bool {
+        true
+    }
+
+    fn bound(&self) -> Option<BBox> {
+        Some(BBox::from_point(self.pos))
+    }
+}
@@ -0,0 +1,290 @@
+use core::{
+    geometry::{Point3f, Vector3f, RayDifferential, coordinate_system, spherical_direction, spherical_theta, spherical_phi},
+    light::{Light, LightSample, VisibilityTester},
+    math::{clamp, radians},
+    montecarlo::{Distribution2D, uniform_sample_cone, uniform_cone_pdf},
+    scene::Scene,
+    spectrum::Spectrum,
+    transform::Transform,
+    types::{Float, PI, INV_PI, INV_TWO_PI},
+};
+use cgmath::prelude::*;
+
+// Angular radius of the solar disk in radians
+const SUN_ANGULAR_RADIUS: Float = 0.00465;
+
+// Approximate extraterrestrial luminance of the sun in the same $kcd/m^2$ units as the sky model
+const SUN_LUMINANCE: Float = 2.0e6;
+
+const SKY_TABLE_WIDTH: usize = 128;
+const SKY_TABLE_HEIGHT: usize = 64;
+
+// Preetham et al. analytic daylight model. Directions are given in a frame with $z$ pointing to the zenith.
+#[derive(Clone)]
+pub struct SkyModel {
+    turbidity: Float,
+    theta_sun: Float,
+    sun_dir: Vector3f,
+    perez: [[Float; 5]; 3],
+    zenith: [Float; 3],
+}
+
+impl SkyModel {
+    pub fn new(turbidity: Float, sun_elevation: Float, sun_azimuth: Float) -> SkyModel {
+        let theta_sun = radians(90.0 - sun_elevation);
+        let sun_dir = spherical_direction(theta_sun.sin(), theta_sun.cos(), radians(sun_azimuth));
+
+        // Compute Perez distribution coefficients for luminance and chromaticity
+        let t = turbidity;
+        let perez = [
+            [0.1787 * t - 1.4630, -0.3554 * t + 0.4275, -0.0227 * t + 5.3251, 0.1206 * t - 2.5771, -0.0670 * t + 0.3703],
+            [-0.0193 * t - 0.2592, -0.0665 * t + 0.0008, -0.0004 * t + 0.2125, -0.0641 * t - 0.8989, -0.0033 * t + 0.0452],
+            [-0.0167 * t - 0.2608, -0.0950 * t + 0.0092, -0.0079 * t + 0.2102, -0.0441 * t - 1.6537, -0.0109 * t + 0.0529],
+        ];
+
+        // Compute zenith luminance and chromaticity, the model is only valid for a sun above the horizon
+        let ts = theta_sun.min(PI / 2.0);
+        let (ts2, ts3, t2) = (ts * ts, ts * ts * ts, t * t);
+        let chi = (4.0 / 9.0 - t / 120.0) * (PI - 2.0 * ts);
+        let zenith_y = (4.0453 * t - 4.9710) * chi.tan() - 0.2155 * t + 2.4192;
+        let zenith_x = t2 * (0.00166 * ts3 - 0.00375 * ts2 + 0.00209 * ts) +
+            t * (-0.02903 * ts3 + 0.06377 * ts2 - 0.03202 * ts + 0.00394) +
+            (0.11693 * ts3 - 0.21196 * ts2 + 0.06052 * ts + 0.25886);
+        let zenith_cy = t2 * (0.00275 * ts3 - 0.00610 * ts2 + 0.00317 * ts) +
+            t * (-0.04214 * ts3 + 0.08970 * ts2 - 0.04153 * ts + 0.00516) +
+            (0.15346 * ts3 - 0.26756 * ts2 + 0.06670 * ts + 0.26688);
+
+        SkyModel {
+            turbidity,
+            theta_sun: ts,
+            sun_dir,
+            perez,
+            zenith: [zenith_y.max(0.0), zenith_x, zenith_cy],
+        }
+    }
+
+    pub fn sun_direction(&self) -> Vector3f {
+        self.sun_dir
+    }
+
+    fn perez(&self, i: usize, costheta: Float, gamma: Float) -> Float {
+        let [a, b, c, d, e] = self.perez[i];
+        (1.0 + a * (b / costheta).exp()) * (1.0 + c * (d * gamma).exp() + e * gamma.cos() * gamma.cos())
+    }
+
+    pub fn sky_radiance(&self, w: &Vector3f) -> Spectrum {
+        if w.z <= 0.0 {
+            return Spectrum::black();
+        }
+
+        // Evaluate Perez functions relative to their zenith values
+        let costheta = w.z.max(1e-3);
+        let gamma = clamp(w.dot(self.sun_dir), -1.0, 1.0).acos();
+        let value = |i: usize| {
+            self.zenith[i] * self.perez(i, costheta, gamma) / self.perez(i, 1.0, self.theta_sun)
+        };
+        let (lum, cx, cy) = (value(0), value(1), value(2));
+
+        // Convert $xyY$ to RGB
+        Spectrum::from_xyz(cx / cy * lum, lum, (1.0 - cx - cy) / cy * lum).clamp_positive()
+    }
+
+    pub fn sun_radiance(&self) -> Spectrum {
+        if self.sun_dir.z <= 0.0 {
+            return Spectrum::black();
+        }
+
+        // Compute relative optical mass of the atmosphere along the sun direction
+        let theta_degrees = self.theta_sun * 180.0 / PI;
+        let m = 1.0 / (self.theta_sun.cos() + 0.15 * (93.885 - theta_degrees).powf(-1.253));
+
+        // Attenuate by Rayleigh and aerosol scattering at representative wavelengths (in micrometers),
+        // with Angstrom's turbidity coefficient as published by Preetham et al.
+        #[allow(clippy::excessive_precision)]
+        let beta = 0.04608365822 * self.turbidity - 0.04586025928;
+        let transmittance = |lambda: Float| {
+            let tau_r = (-0.008735 * lambda.powf(-4.08) * m).exp();
+            let tau_a = (-beta * lambda.powf(-1.3) * m).exp();
+            tau_r * tau_a
+        };
+
+        SUN_LUMINANCE * Spectrum::new(transmittance(0.65), transmittance(0.55), transmittance(0.45))
+    }
+
+    fn sun_solid_angle(&self) -> Float {
+        2.0 * PI * (1.0 - SUN_ANGULAR_RADIUS.cos())
+    }
+}
+
+pub struct SkyLight {
+    light_to_world: Transform,
+    world_to_light: Transform,
+    model: SkyModel,
+    ground: Spectrum,
+    scale: Float,
+    average: Spectrum,
+    distribution: Distribution2D,
+    num_samples: u32,
+}
+
+impl SkyLight {
+    pub fn new(light_to_world: Transform, model: &SkyModel, ground_albedo: Spectrum, scale: Float, num_samples: u32) -> SkyLight {
+        // Tabulate sky radiance for importance sampling and irradiance estimates
+        let dtheta = PI / SKY_TABLE_HEIGHT as Float;
+        let dphi = 2.0 * PI / SKY_TABLE_WIDTH as Float;
+        let mut img = Vec::with_capacity(SKY_TABLE_WIDTH * SKY_TABLE_HEIGHT);
+        let mut sky_integral = Spectrum::black();
+        let mut sky_irradiance = Spectrum::black();
+        for v in 0..SKY_TABLE_HEIGHT {
+            let theta = (v as Float + 0.5) * dtheta;
+            let (sintheta, costheta) = theta.sin_cos();
+            for u in 0..SKY_TABLE_WIDTH {
+                let phi = (u as Float + 0.5) * dphi;
+                let l = model.sky_radiance(&spherical_direction(sintheta, costheta, phi));
+                sky_integral += l * sintheta * dtheta * dphi;
+                sky_irradiance += l * costheta.max(0.0) * sintheta * dtheta * dphi;
+                img.push(l.y() * sintheta);
+            }
+        }
+
+        // Compute ground radiance from diffuse reflection of sky and sun
+        let sun_irradiance = model.sun_radiance() * model.sun_solid_angle() * model.sun_dir.z.max(0.0);
+        let ground = ground_albedo * (sky_irradiance + sun_irradiance) * INV_PI;
+        for v in (SKY_TABLE_HEIGHT / 2)..SKY_TABLE_HEIGHT {
+            let sintheta = ((v as Float + 0.5) * dtheta).sin();
+            for u in 0..SKY_TABLE_WIDTH {
+                img[v * SKY_TABLE_WIDTH + u] = ground.y() * sintheta;
+            }
+        }
+
+        let average = (sky_integral + ground * 2.0 * PI) / (4.0 * PI);
+
+        SkyLight {
+            world_to_light: light_to_world.invert(),
+            light_to_world,
+            model: model.clone(),
+            ground,
+            scale,
+            average,
+            distribution: Distribution2D::new(&img, SKY_TABLE_WIDTH, SKY_TABLE_HEIGHT),
+            num_samples,
+        }
+    }
+
+    fn radiance(&self, w: &Vector3f) -> Spectrum {
+        if w.z > 0.0 {
+            self.scale * self.model.sky_radiance(w)
+        } else {
+            self.scale * self.ground
+        }
+    }
+}
+
+impl Light for SkyLight {
+    fn sample_l(&self, p: &Point3f, p_epsilon: Float, ls: &LightSample, time: Float, visibility: &mut VisibilityTester) -> (Spectrum, Vector3f, Float) {
+        // Find $(u,v)$ sample coordinates in tabulated sky
+        let (uv, map_pdf) = self.distribution.sample_continuous(ls.u_pos[0], ls.u_pos[1]);
+        if map_pdf == 0.0 {
+            return (Spectrum::black(), Vector3f::unit_z(), 0.0);
+        }
+
+        // Convert sample point to direction
+        let theta = uv[1] * PI;
+        let phi = uv[0] * 2.0 * PI;
+        let sintheta = theta.sin();
+        let wl = spherical_direction(sintheta, theta.cos(), phi);
+        let wi = self.light_to_world.transform_vector(wl);
+
+        let pdf = if sintheta == 0.0 { 0.0 } else { map_pdf / (2.0 * PI * PI * sintheta) };
+
+        visibility.set_ray(*p, p_epsilon, wi, time);
+        (self.radiance(&wl), wi, pdf)
+    }
+
+    fn le(&self, ray: &RayDifferential) -> Spectrum {
+        self.radiance(&self.world_to_light.transform_vector(ray.ray.d).normalize())
+    }
+
+    fn pdf(&self, _p: &Point3f, w: &Vector3f) -> Float {
+        let wi = self.world_to_light.transform_vector(*w).normalize();
+        let theta = spherical_theta(&wi);
+        let phi = spherical_phi(&wi);
+        let sintheta = theta.sin();
+        if sintheta == 0.0 {
+            return 0.0;
+        }
+        self.distribution.pdf(phi * INV_TWO_PI, theta * INV_PI) / (2.0 * PI * PI * sintheta)
+    }
+
+    fn power(&self, scene: &Scene) -> Spectrum {
+        let (_, world_radius) = scene.world_bound().bounding_sphere();
+        PI * world_radius * world_radius * self.scale * self.average
+    }
+
+    fn num_samples(&self) -> u32 {
+        self.num_samples
+    }
+
+    fn is_delta_light(&self) -> bool {
+        false
+    }
+}
+
+pub struct SunLight {
+    sun_dir: Vector3f,
+    sun_x: Vector3f,
+    sun_y: Vector3f,
+    cos_max: Float,
+    radiance: Spectrum,
+    solid_angle: Float,
+    num_samples: u32,
+}
+
+impl SunLight {
+    pub fn new(light_to_world: &Transform, model: &SkyModel, scale: Float, num_samples: u32) -> SunLight {
+        let sun_dir = light_to_world.transform_vector(model.sun_direction()).normalize();
+        let (sun_x, sun_y) = coordinate_system(&sun_dir);
+        SunLight {
+            sun_dir,
+            sun_x,
+            sun_y,
+            cos_max: SUN_ANGULAR_RADIUS.cos(),
+            radiance: scale * model.sun_radiance(),
+            solid_angle: model.sun_solid_angle(),
+            num_samples,
+        }
+    }
+
+    fn in_disk(&self, w: &Vector3f) -> bool {
+        w.normalize().dot(self.sun_dir) >= self.cos_max
+    }
+}
+
+impl Light for SunLight {
+    fn sample_l(&self, p: &Point3f, p_epsilon: Float, ls: &LightSample, time: Float, visibility: &mut VisibilityTester) -> (Spectrum, Vector3f, Float) {
+        let wi = uniform_sample_cone(ls.u_pos[0], ls.u_pos[1], self.cos_max, &self.sun_x, &self.sun_y, &self.sun_dir);
+        visibility.set_ray(*p, p_epsilon, wi, time);
+        (self.radiance, wi, uniform_cone_pdf(self.cos_max))
+    }
+
+    fn le(&self, ray: &RayDifferential) -> Spectrum {
+        if self.in_disk(&ray.ray.d) { self.radiance } else { Spectrum::black() }
+    }
+
+    fn pdf(&self, _p: &Point3f, wi: &Vector3f) -> Float {
+        if self.in_disk(wi) { uniform_cone_pdf(self.cos_max) } else { 0.0 }
+    }
+
+    fn power(&self, scene: &Scene) -> Spectrum {
+        let (_, world_radius) = scene.world_bound().bounding_sphere();
+        self.radiance * self.solid_angle * PI * world_radius * world_radius
+    }
+
+    fn num_samples(&self) -> u32 {
+        self.num_samples
+    }
+
+    fn is_delta_light(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,78 @@
+use core::light::Light;
+use core::spectrum::Spectrum;
+use core::geometry::{Point3f, BBox};
+use core::light::LightSample;
+use core::light::VisibilityTester;
+use core::geometry::Vector3f;
+use core::types::{PI, Float};
+use core::geometry::distance_squared;
+use core::transform::Transform;
+use core::math::radians;
+use cgmath::prelude::*;
+use core::scene::Scene;
+
+pub struct SpotLight {
+    world_to_light: Transform,
+    pos: Point3f,
+    intensity: Spectrum,
+    cos_total_width: Float,
+    cos_falloff_start: Float,
+}
+
+impl SpotLight {
+    pub fn new(light_to_world: Transform, intensity: Spectrum, width: Float, falloff_start: Float) -> SpotLight {
+        let world_to_light = light_to_world.invert();
+        let pos = light_to_world.transform_point(Point3f::new(0.0, 0.0, 0.0));
+        SpotLight {
+            world_to_light,
+            pos,
+            intensity,
+            cos_total_width: radians(width).cos(),
+            cos_falloff_start: radians(falloff_start).cos(),
+        }
+    }
+
+    fn falloff(&self, w: &Vector3f) -> Float {
+        let wl = self.world_to_light.transform_vector(*w).normalize();
+        let costheta = wl.z;
+        if costheta < self.cos_total_width {
+            return 0.0;
+        }
+        if costheta > self.cos_falloff_start {
+            return 1.0;
+        }
+
+        // Compute falloff inside spotlight cone
+        let delta = (costheta - self.cos_total_width) / (self.cos_falloff_start - self.cos_total_width);
+        delta * delta * delta * delta
+    }
+}
+
+impl Light for SpotLight {
+    fn sample_l(&self, p: &Point3f, p_epsilon: Float, _: &LightSample, time: Float, visibility: &mut VisibilityTester) -> (Spectrum, Vector3f, Float) {
+        let wi = (self.pos - p).normalize();
+        visibility.set_segment(*p, p_epsilon, self.pos, 0.0, time);
+        let c = self.intensity * self.falloff(&-wi) / distance_squared(&self.pos, p);
+        (c, wi, 1.0)
+    }
+
+    fn pdf(&self, _p: &Point3f, _wi: &Vector3f) -> Float {
+        0.0
+    }
+
+    fn power(&self, _scene: &Scene) -> Spectrum {
+        self.intensity * 2.0 * PI * (1.0 - 0.5 * (self.cos_falloff_start + self.cos_total_width))
+    }
+
+    fn num_samples(&self) -> u32 {
+        1
+    }
+
+    fn is_delta_light(&self) -> bool {
+        true
+    }
+
+    fn bound(&self) -> Option<BBox> {
+        Some(BBox::from_point(self.pos))
+    }
+}
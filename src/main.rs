@@ -23,7 +23,7 @@ use rpbtrir::{
     films::ImageFilm,
     filters::MitchellFilter,
     lights::PointLight,
-    materials::{MatteMaterial, MetalMaterial, MirrorMaterial},
+    materials::{MatteMaterial, MetalMaterial, MirrorMaterial, NamedMetal},
     renderers::SamplerRenderer,
     shapes::Sphere,
     textures::{ConstantTexture, Checkerboard2DTexture, AAMethod},
@@ -104,10 +104,8 @@ fn diffuse_area_light(center: Point3f, intensity: Float) -> Arc<AreaLight> {
 }
 
 fn metal() -> Box<Material> {
-    let eta = Arc::new(ConstantTexture::new(Spectrum::new(0.4, 0.2, 0.4)));
-    let k = Arc::new(ConstantTexture::new(Spectrum::new(0.9, 0.4, 0.5)));
-    let roughness = Arc::new(ConstantTexture::new(0.05));
-    Box::new(MetalMaterial::new(eta, k, roughness, None))
+    let roughness = Arc::new(ConstantTexture::new(0.5));
+    Box::new(MetalMaterial::new_named(NamedMetal::Copper, roughness, true, None, None))
 }
 
 fn glass() -> Box<Material> {
@@ -129,6 +127,7 @@ fn checker_matte(scale: Float) -> Box<Material> {
         Arc::new(checker),
         Arc::new(ConstantTexture::new(0.0)),
         None,
+        None,
     ))
 }
 
@@ -136,8 +135,8 @@ fn geometric_primitive(shape: Box<Shape>, material: Box<Material>, area_light: O
     Box::new(GeometricPrimitive::new(shape, material, area_light))
 }
 
-fn point_light_white(point: Point3f, intensity: Float) -> Box<Light> {
-    Box::new(PointLight::new(point, intensity * Spectrum::white()))
+fn point_light_white(point: Point3f, intensity: Float) -> Arc<Light> {
+    Arc::new(PointLight::new(point, intensity * Spectrum::white()))
 }
 
 fn sphere(center: Point3f, radius: Float) -> Box<Shape> {
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+use core::{
+    differential_geometry::DifferentialGeometry,
+    material::{Material, NormalMap, shading_geometry},
+    reflection::{BSDF, BxDFType, CoatedBxDF, CoatedSpecularBxDF, FresnelDielectric, Microfacet, SpecularReflection, TrowbridgeReitz, roughness_to_alpha},
+    spectrum::Spectrum,
+    texture::Texture,
+    types::Float,
+};
+
+// Clear dielectric layer over another material, such as varnish or lacquer. Light passing through
+// the layer is absorbed according to _sigma_a_ over the distance travelled within _thickness_.
+// The base lobes are evaluated in the coat's shading frame, so any bump or normal map of the base
+// material itself is ignored, as is its index of refraction since only its reflection is kept.
+pub struct CoatedMaterial {
+    base: Box<Material>,
+    index: Arc<Texture<Float>>,
+    roughness: Arc<Texture<Float>>,
+    remap_roughness: bool,
+    sigma_a: Arc<Texture<Spectrum>>,
+    thickness: Arc<Texture<Float>>,
+    bump_map: Option<Arc<Texture<Float>>>,
+    normal_map: Option<Arc<NormalMap>>,
+}
+
+impl CoatedMaterial {
+    pub fn new(base: Box<Material>,
+               index: Arc<Texture<Float>>,
+               roughness: Arc<Texture<Float>>,
+               remap_roughness: bool,
+               sigma_a: Arc<Texture<Spectrum>>,
+               thickness: Arc<Texture<Float>>,
+               bump_map: Option<Arc<Texture<Float>>>,
+               normal_map: Option<Arc<NormalMap>>) -> CoatedMaterial {
+        CoatedMaterial { base, index, roughness, remap_roughness, sigma_a, thickness, bump_map, normal_map }
+    }
+}
+
+impl Material for CoatedMaterial {
+    fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
+        let dgs = shading_geometry(&self.bump_map, &self.normal_map, dg_geom, dg_shading);
+
+        // Evaluate textures for _CoatedMaterial_ material
+        let ior = self.index.evaluate(&dgs);
+        let sigma_a = self.sigma_a.evaluate(&dgs).clamp_positive();
+        let thickness = self.thickness.evaluate(&dgs).max(0.0);
+        let mut rough = self.roughness.evaluate(&dgs);
+        if self.remap_roughness && rough != 0.0 {
+            rough = roughness_to_alpha(rough);
+        }
+
+        // Gather lobes of the base material seen through the coat, specular ones are sampled separately
+        let (specular, base): (Vec<_>, Vec<_>) = self.base.get_bsdf(dg_geom, &dgs).into_bxdfs().into_iter()
+            .partition(|b| b.bxdf_type().contains(BxDFType::BSDF_SPECULAR));
+
+        let mut bsdf = BSDF::new(dgs, dg_geom.nn);
+        let fresnel = Box::new(FresnelDielectric::new(1.0, ior));
+        if rough == 0.0 {
+            bsdf.add(Box::new(SpecularReflection::new(Spectrum::white(), fresnel)));
+        } else {
+            let distribution = TrowbridgeReitz::new(rough, rough);
+            bsdf.add(Box::new(Microfacet::new(Spectrum::white(), fresnel, Box::new(distribution))));
+        }
+        if !base.is_empty() {
+            bsdf.add(Box::new(CoatedBxDF::new(base, ior, sigma_a, thickness)));
+        }
+        for b in specular.into_iter().filter(|b| b.bxdf_type().contains(BxDFType::BSDF_REFLECTION)) {
+            bsdf.add(Box::new(CoatedSpecularBxDF::new(b, ior, sigma_a, thickness)));
+        }
+
+        bsdf
+    }
+}
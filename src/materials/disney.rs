@@ -0,0 +1,401 @@
+use std::sync::Arc;
+use cgmath::prelude::*;
+use core::{
+    differential_geometry::DifferentialGeometry,
+    geometry::{Vector3f, spherical_direction},
+    material::{Material, NormalMap, shading_geometry},
+    math::{clamp, lerp},
+    montecarlo::cosine_sample_hemisphere,
+    reflection::{BSDF, BxDF, BxDFType, Fresnel, FresnelDielectric, Microfacet, MicrofacetTransmission},
+    reflection::{SpecularTransmission, TrowbridgeReitz},
+    reflection::{abs_cos_theta, half_vector, reflect, same_hemisphere},
+    spectrum::Spectrum,
+    texture::Texture,
+    types::{Float, INV_PI, PI},
+};
+use textures::ConstantTexture;
+
+// Parameters of the Disney BRDF, each in $[0,1]$. _Default_ gives Burley's defaults.
+pub struct DisneyParameters {
+    pub color: Arc<Texture<Spectrum>>,
+    pub metallic: Arc<Texture<Float>>,
+    pub roughness: Arc<Texture<Float>>,
+    pub specular: Arc<Texture<Float>>,
+    pub specular_tint: Arc<Texture<Float>>,
+    pub anisotropic: Arc<Texture<Float>>,
+    pub sheen: Arc<Texture<Float>>,
+    pub sheen_tint: Arc<Texture<Float>>,
+    pub clearcoat: Arc<Texture<Float>>,
+    pub clearcoat_gloss: Arc<Texture<Float>>,
+    pub spec_trans: Arc<Texture<Float>>,
+    pub subsurface: Arc<Texture<Float>>,
+}
+
+impl Default for DisneyParameters {
+    fn default() -> Self {
+        DisneyParameters {
+            color: Arc::new(ConstantTexture::new(Spectrum::from(0.5))),
+            metallic: Arc::new(ConstantTexture::new(0.0)),
+            roughness: Arc::new(ConstantTexture::new(0.5)),
+            specular: Arc::new(ConstantTexture::new(0.5)),
+            specular_tint: Arc::new(ConstantTexture::new(0.0)),
+            anisotropic: Arc::new(ConstantTexture::new(0.0)),
+            sheen: Arc::new(ConstantTexture::new(0.0)),
+            sheen_tint: Arc::new(ConstantTexture::new(0.5)),
+            clearcoat: Arc::new(ConstantTexture::new(0.0)),
+            clearcoat_gloss: Arc::new(ConstantTexture::new(1.0)),
+            spec_trans: Arc::new(ConstantTexture::new(0.0)),
+            subsurface: Arc::new(ConstantTexture::new(0.0)),
+        }
+    }
+}
+
+// Principled material after Burley's Disney BRDF, with the extensions for specular transmission
+// from his 2015 notes. Subsurface scattering is approximated by the Hanrahan-Krueger inspired
+// diffuse lobe of the original model.
+pub struct DisneyMaterial {
+    params: DisneyParameters,
+    bump_map: Option<Arc<Texture<Float>>>,
+    normal_map: Option<Arc<NormalMap>>,
+}
+
+impl DisneyMaterial {
+    pub fn new(params: DisneyParameters,
+               bump_map: Option<Arc<Texture<Float>>>,
+               normal_map: Option<Arc<NormalMap>>) -> DisneyMaterial {
+        DisneyMaterial { params, bump_map, normal_map }
+    }
+}
+
+impl Material for DisneyMaterial {
+    fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
+        let dgs = shading_geometry(&self.bump_map, &self.normal_map, dg_geom, dg_shading);
+
+        // Evaluate textures for _DisneyMaterial_ material
+        let c = self.params.color.evaluate(&dgs).clamp_positive();
+        let metallic_weight = self.params.metallic.evaluate(&dgs);
+        let rough = self.params.roughness.evaluate(&dgs);
+        let specular = self.params.specular.evaluate(&dgs);
+        let strans = self.params.spec_trans.evaluate(&dgs);
+        let subsurface = self.params.subsurface.evaluate(&dgs);
+        let diffuse_weight = (1.0 - metallic_weight) * (1.0 - strans);
+
+        // Relative index of refraction implied by the specular reflectance at normal incidence
+        let r0 = 0.08 * specular;
+        let e = if r0 > 0.0 { (1.0 + r0.sqrt()) / (1.0 - r0.sqrt()).max(1e-3) } else { 1.0 };
+
+        // Normalize luminance of the base color to get its hue and saturation
+        let lum = c.y();
+        let c_tint = if lum > 0.0 { c / lum } else { Spectrum::white() };
+
+        let mut bsdf = BSDF::new_with_eta(dgs.clone(), dg_geom.nn, e);
+
+        // Add diffuse, retro-reflection and sheen lobes for the non-metallic base
+        if diffuse_weight > 0.0 {
+            if subsurface < 1.0 {
+                bsdf.add(Box::new(DisneyDiffuse::new(diffuse_weight * (1.0 - subsurface) * c)));
+            }
+            if subsurface > 0.0 {
+                bsdf.add(Box::new(DisneyFakeSS::new(diffuse_weight * subsurface * c, rough)));
+            }
+            bsdf.add(Box::new(DisneyRetro::new(diffuse_weight * c, rough)));
+
+            let sheen_weight = self.params.sheen.evaluate(&dgs);
+            if sheen_weight > 0.0 {
+                let stint = self.params.sheen_tint.evaluate(&dgs);
+                let c_sheen = (1.0 - stint) * Spectrum::white() + stint * c_tint;
+                bsdf.add(Box::new(DisneySheen::new(diffuse_weight * sheen_weight * c_sheen)));
+            }
+        }
+
+        // Create the microfacet distribution for metallic and specular reflection
+        let aspect = (1.0 - self.params.anisotropic.evaluate(&dgs) * 0.9).sqrt();
+        let ax = (rough * rough / aspect).max(0.001);
+        let ay = (rough * rough * aspect).max(0.001);
+
+        // Specular is Schlick's approximation blended toward the base color as the material becomes metallic
+        let spec_tint = self.params.specular_tint.evaluate(&dgs);
+        let dielectric_spec0 = r0 * ((1.0 - spec_tint) * Spectrum::white() + spec_tint * c_tint);
+        let c_spec0 = (1.0 - metallic_weight) * dielectric_spec0 + metallic_weight * c;
+        let fresnel = DisneyFresnel::new(c_spec0, metallic_weight, e);
+        bsdf.add(Box::new(Microfacet::new(Spectrum::white(), Box::new(fresnel), Box::new(TrowbridgeReitz::new(ax, ay)))));
+
+        // Clearcoat
+        let cc = self.params.clearcoat.evaluate(&dgs);
+        if cc > 0.0 {
+            let gloss = lerp(self.params.clearcoat_gloss.evaluate(&dgs), 0.1, 0.001);
+            bsdf.add(Box::new(DisneyClearcoat::new(cc, gloss)));
+        }
+
+        // Specular transmission, tinted by the square root of the base color for both interfaces
+        if strans > 0.0 {
+            let t = strans * Spectrum::new(c.r.sqrt(), c.g.sqrt(), c.b.sqrt());
+            if rough == 0.0 {
+                bsdf.add(Box::new(SpecularTransmission::new(t, 1.0, e)));
+            } else {
+                bsdf.add(Box::new(MicrofacetTransmission::new(t, Box::new(TrowbridgeReitz::new(ax, ay)), 1.0, e)));
+            }
+        }
+
+        bsdf
+    }
+}
+
+#[inline]
+fn schlick_weight(cos_theta: Float) -> Float {
+    let m = clamp(1.0 - cos_theta, 0.0, 1.0);
+    (m * m) * (m * m) * m
+}
+
+#[inline]
+fn fr_schlick(r0: Spectrum, cos_theta: Float) -> Spectrum {
+    r0 + schlick_weight(cos_theta) * (Spectrum::white() - r0)
+}
+
+// Cosine-weighted sampling shared by the diffuse Disney lobes
+fn sample_cosine(bxdf: &BxDF, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+    *wi = cosine_sample_hemisphere(u1, u2);
+    if wo.z < 0.0 {
+        wi.z *= -1.0;
+    }
+    (bxdf.f(&wo, wi), bxdf.pdf(&wo, wi))
+}
+
+fn diffuse_type() -> BxDFType {
+    BxDFType::BSDF_REFLECTION | BxDFType::BSDF_DIFFUSE
+}
+
+struct DisneyDiffuse {
+    r: Spectrum,
+}
+
+impl DisneyDiffuse {
+    fn new(r: Spectrum) -> DisneyDiffuse {
+        DisneyDiffuse { r }
+    }
+}
+
+impl BxDF for DisneyDiffuse {
+    fn f(&self, wo: &Vector3f, wi: &Vector3f) -> Spectrum {
+        let fo = schlick_weight(abs_cos_theta(wo));
+        let fi = schlick_weight(abs_cos_theta(wi));
+
+        // Diffuse fresnel, going from 1 at normal incidence to 0.5 at grazing
+        self.r * INV_PI * (1.0 - fo / 2.0) * (1.0 - fi / 2.0)
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        sample_cosine(self, wo, wi, u1, u2)
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        diffuse_type()
+    }
+}
+
+// Approximation of isotropic single scattering under the surface
+struct DisneyFakeSS {
+    r: Spectrum,
+    roughness: Float,
+}
+
+impl DisneyFakeSS {
+    fn new(r: Spectrum, roughness: Float) -> DisneyFakeSS {
+        DisneyFakeSS { r, roughness }
+    }
+}
+
+impl BxDF for DisneyFakeSS {
+    fn f(&self, wo: &Vector3f, wi: &Vector3f) -> Spectrum {
+        let wh = match half_vector(wo, wi) {
+            Some(wh) => wh,
+            None => return Spectrum::black(),
+        };
+        let cos_theta_d = wi.dot(wh);
+
+        // Fss90 used to "flatten" retroreflection based on roughness
+        let fss90 = cos_theta_d * cos_theta_d * self.roughness;
+        let fo = schlick_weight(abs_cos_theta(wo));
+        let fi = schlick_weight(abs_cos_theta(wi));
+        let fss = lerp(fo, 1.0, fss90) * lerp(fi, 1.0, fss90);
+
+        // 1.25 scale is used to (roughly) preserve albedo
+        let ss = 1.25 * (fss * (1.0 / (abs_cos_theta(wo) + abs_cos_theta(wi)) - 0.5) + 0.5);
+        self.r * INV_PI * ss
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        sample_cosine(self, wo, wi, u1, u2)
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        diffuse_type()
+    }
+}
+
+struct DisneyRetro {
+    r: Spectrum,
+    roughness: Float,
+}
+
+impl DisneyRetro {
+    fn new(r: Spectrum, roughness: Float) -> DisneyRetro {
+        DisneyRetro { r, roughness }
+    }
+}
+
+impl BxDF for DisneyRetro {
+    fn f(&self, wo: &Vector3f, wi: &Vector3f) -> Spectrum {
+        let wh = match half_vector(wo, wi) {
+            Some(wh) => wh,
+            None => return Spectrum::black(),
+        };
+        let cos_theta_d = wi.dot(wh);
+
+        let fo = schlick_weight(abs_cos_theta(wo));
+        let fi = schlick_weight(abs_cos_theta(wi));
+        let rr = 2.0 * self.roughness * cos_theta_d * cos_theta_d;
+
+        // Burley 2015, eq (4)
+        self.r * INV_PI * rr * (fo + fi + fo * fi * (rr - 1.0))
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        sample_cosine(self, wo, wi, u1, u2)
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        diffuse_type()
+    }
+}
+
+struct DisneySheen {
+    r: Spectrum,
+}
+
+impl DisneySheen {
+    fn new(r: Spectrum) -> DisneySheen {
+        DisneySheen { r }
+    }
+}
+
+impl BxDF for DisneySheen {
+    fn f(&self, wo: &Vector3f, wi: &Vector3f) -> Spectrum {
+        match half_vector(wo, wi) {
+            Some(wh) => self.r * schlick_weight(wi.dot(wh)),
+            None => Spectrum::black(),
+        }
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        sample_cosine(self, wo, wi, u1, u2)
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        diffuse_type()
+    }
+}
+
+// Generalized Trowbridge-Reitz distribution with $\gamma=1$
+#[inline]
+fn gtr1(cos_theta: Float, alpha: Float) -> Float {
+    let alpha2 = alpha * alpha;
+    (alpha2 - 1.0) / (PI * alpha2.ln() * (1.0 + (alpha2 - 1.0) * cos_theta * cos_theta))
+}
+
+// Smith masking for GGX with fixed roughness _alpha_
+#[inline]
+fn smith_g_ggx(cos_theta: Float, alpha: Float) -> Float {
+    let alpha2 = alpha * alpha;
+    let cos_theta2 = cos_theta * cos_theta;
+    1.0 / (cos_theta + (alpha2 + cos_theta2 - alpha2 * cos_theta2).sqrt())
+}
+
+struct DisneyClearcoat {
+    weight: Float,
+    gloss: Float,
+}
+
+impl DisneyClearcoat {
+    fn new(weight: Float, gloss: Float) -> DisneyClearcoat {
+        DisneyClearcoat { weight, gloss }
+    }
+}
+
+impl BxDF for DisneyClearcoat {
+    fn f(&self, wo: &Vector3f, wi: &Vector3f) -> Spectrum {
+        let wh = match half_vector(wo, wi) {
+            Some(wh) => wh,
+            None => return Spectrum::black(),
+        };
+
+        // Clearcoat has ior = 1.5 hardcoded -> F0 = 0.04. It then uses the gtr1 distribution,
+        // which has even fatter tails than Trowbridge-Reitz
+        let dr = gtr1(abs_cos_theta(&wh), self.gloss);
+        let fr = fr_schlick(Spectrum::from(0.04), wo.dot(wh));
+        // The geometric term always based on alpha = 0.25
+        let gr = smith_g_ggx(abs_cos_theta(wo), 0.25) * smith_g_ggx(abs_cos_theta(wi), 0.25);
+
+        self.weight * gr * fr * dr / 4.0
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        if wo.z == 0.0 {
+            return (Spectrum::black(), 0.0);
+        }
+
+        // Sample half vector from the gtr1 distribution
+        let alpha2 = self.gloss * self.gloss;
+        let cos_theta = ((1.0 - alpha2.powf(1.0 - u1)) / (1.0 - alpha2)).max(0.0).sqrt();
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * u2;
+        let mut wh = spherical_direction(sin_theta, cos_theta, phi);
+        if !same_hemisphere(&wo, &wh) {
+            wh = -wh;
+        }
+
+        *wi = reflect(&wo, &wh);
+        if !same_hemisphere(&wo, wi) {
+            return (Spectrum::black(), 0.0);
+        }
+        (self.f(&wo, wi), self.pdf(&wo, wi))
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        BxDFType::BSDF_REFLECTION | BxDFType::BSDF_GLOSSY
+    }
+
+    fn pdf(&self, wo: &Vector3f, wi: &Vector3f) -> Float {
+        if !same_hemisphere(wo, wi) {
+            return 0.0;
+        }
+        let wh = match half_vector(wo, wi) {
+            Some(wh) => wh,
+            None => return 0.0,
+        };
+
+        // The sampling routine samples wh exactly from the gtr1 distribution, so the final
+        // pdf only needs the usual reflection Jacobian
+        let dr = gtr1(abs_cos_theta(&wh), self.gloss);
+        dr * abs_cos_theta(&wh) / (4.0 * wo.dot(wh))
+    }
+}
+
+// Dielectric Fresnel blended with Schlick's approximation for the metallic part
+struct DisneyFresnel {
+    r0: Spectrum,
+    metallic: Float,
+    dielectric: FresnelDielectric,
+}
+
+impl DisneyFresnel {
+    fn new(r0: Spectrum, metallic: Float, eta: Float) -> DisneyFresnel {
+        DisneyFresnel { r0, metallic, dielectric: FresnelDielectric::new(1.0, eta) }
+    }
+}
+
+impl Fresnel for DisneyFresnel {
+    fn evaluate(&self, cosi: Float) -> Spectrum {
+        (1.0 - self.metallic) * self.dielectric.evaluate(cosi) + self.metallic * fr_schlick(self.r0, cosi)
+    }
+}
@@ -1,8 +1,8 @@
 use std::sync::Arc;
 use core::{
     differential_geometry::DifferentialGeometry,
-    material::{Material, bump},
-    reflection::BSDF,
+    material::{Material, NormalMap, shading_geometry},
+    reflection::{BSDF, Microfacet, MicrofacetTransmission, TrowbridgeReitz, roughness_to_alpha},
     spectrum::Spectrum,
     texture::Texture,
     types::Float,
@@ -16,31 +16,56 @@ pub struct GlassMaterial {
     kr: Arc<Texture<Spectrum>>,
     kt: Arc<Texture<Spectrum>>,
     index: Arc<Texture<Float>>,
+    roughness: Arc<Texture<Float>>,
+    remap_roughness: bool,
     bump_map: Option<Arc<Texture<Float>>>,
+    normal_map: Option<Arc<NormalMap>>,
 }
 
 impl GlassMaterial {
+    // Zero _roughness_ gives smooth glass with specular lobes, _remap_roughness_ selects perceptual
+    // roughness instead of microfacet $\alpha$
     pub fn new(kr: Arc<Texture<Spectrum>>,
                kt: Arc<Texture<Spectrum>>,
                index: Arc<Texture<Float>>,
-               bump_map: Option<Arc<Texture<Float>>>) -> GlassMaterial {
-        GlassMaterial { kr, kt, index, bump_map }
+               roughness: Arc<Texture<Float>>,
+               remap_roughness: bool,
+               bump_map: Option<Arc<Texture<Float>>>,
+               normal_map: Option<Arc<NormalMap>>) -> GlassMaterial {
+        GlassMaterial { kr, kt, index, roughness, remap_roughness, bump_map, normal_map }
     }
 }
 
 impl Material for GlassMaterial {
     fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
-        let dgs = self.bump_map.as_ref().map_or_else(|| dg_shading.clone(), |b| bump(b.as_ref(), dg_geom, dg_shading));
+        let dgs = shading_geometry(&self.bump_map, &self.normal_map, dg_geom, dg_shading);
 
         let ior = self.index.evaluate(&dgs);
         let r = self.kr.evaluate(&dgs).clamp_positive();
         let t = self.kt.evaluate(&dgs).clamp_positive();
-        let mut bsdf = BSDF::new_with_eta(dgs, dg_geom.nn, ior);
-        if !r.is_black() {
-            bsdf.add(Box::new(SpecularReflection::new(r, Box::new(FresnelDielectric::new(1.0, ior)))));
+        let mut rough = self.roughness.evaluate(&dgs);
+        if self.remap_roughness && rough != 0.0 {
+            rough = roughness_to_alpha(rough);
         }
-        if !t.is_black() {
-            bsdf.add(Box::new(SpecularTransmission::new(t, 1.0, ior)));
+
+        let mut bsdf = BSDF::new_with_eta(dgs, dg_geom.nn, ior);
+        if rough == 0.0 {
+            if !r.is_black() {
+                bsdf.add(Box::new(SpecularReflection::new(r, Box::new(FresnelDielectric::new(1.0, ior)))));
+            }
+            if !t.is_black() {
+                bsdf.add(Box::new(SpecularTransmission::new(t, 1.0, ior)));
+            }
+        } else {
+            // Use microfacet lobes for rough glass
+            if !r.is_black() {
+                let distribution = TrowbridgeReitz::new(rough, rough);
+                bsdf.add(Box::new(Microfacet::new(r, Box::new(FresnelDielectric::new(1.0, ior)), Box::new(distribution))));
+            }
+            if !t.is_black() {
+                let distribution = TrowbridgeReitz::new(rough, rough);
+                bsdf.add(Box::new(MicrofacetTransmission::new(t, Box::new(distribution), 1.0, ior)));
+            }
         }
 
         return bsdf
@@ -54,7 +79,10 @@ impl Default for GlassMaterial {
             kr: Arc::new(ConstantTexture::new(Spectrum::white())),
             kt: Arc::new(ConstantTexture::new(Spectrum::white())),
             index: Arc::new(ConstantTexture::new(1.5)),
-            bump_map: None
+            roughness: Arc::new(ConstantTexture::new(0.0)),
+            remap_roughness: true,
+            bump_map: None,
+            normal_map: None,
         }
     }
 }
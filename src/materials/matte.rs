@@ -1,7 +1,7 @@
 use core::{
-    material::{Material, bump},
+    material::{Material, NormalMap, shading_geometry},
     differential_geometry::DifferentialGeometry,
-    reflection::{BSDF, Lambertian},
+    reflection::{BSDF, Lambertian, OrenNayar},
     texture::Texture,
     spectrum::Spectrum,
     types::Float,
@@ -13,19 +13,21 @@ pub struct MatteMaterial {
     kd: Arc<Texture<Spectrum>>,
     sigma: Arc<Texture<Float>>,
     bump_map: Option<Arc<Texture<Float>>>,
+    normal_map: Option<Arc<NormalMap>>,
 }
 
 impl MatteMaterial {
     pub fn new(kd: Arc<Texture<Spectrum>>,
                sigma: Arc<Texture<Float>>,
-               bump_map: Option<Arc<Texture<Float>>>) -> MatteMaterial {
-        MatteMaterial { kd, sigma, bump_map }
+               bump_map: Option<Arc<Texture<Float>>>,
+               normal_map: Option<Arc<NormalMap>>) -> MatteMaterial {
+        MatteMaterial { kd, sigma, bump_map, normal_map }
     }
 }
 
 impl Material for MatteMaterial {
     fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
-        let dgs = self.bump_map.as_ref().map_or_else(|| dg_shading.clone(), |b| bump(b.as_ref(), dg_geom, dg_shading));
+        let dgs = shading_geometry(&self.bump_map, &self.normal_map, dg_geom, dg_shading);
 
         // Evaluate textures for _MatteMaterial_ material and allocate BRDF
         let r = self.kd.evaluate(&dgs).clamp_positive();
@@ -35,8 +37,7 @@ impl Material for MatteMaterial {
             if sig == 0.0 {
                 bsdf.add(Box::new(Lambertian::new(r)));
             } else {
-                unimplemented!("OrenNayar is not supported") // TODO
-                //bsdf.add(OrenNayar::new(r, sig));
+                bsdf.add(Box::new(OrenNayar::new(r, sig)));
             }
         }
 
@@ -0,0 +1,342 @@
+use core::{
+    differential_geometry::DifferentialGeometry,
+    geometry::{Vector3f, spherical_direction, spherical_theta, spherical_phi},
+    material::{Material, NormalMap, shading_geometry},
+    montecarlo::{Distribution2D, cosine_sample_hemisphere},
+    reflection::{BSDF, BxDF, BxDFType, abs_cos_theta, reflect, same_hemisphere},
+    spectrum::Spectrum,
+    texture::Texture,
+    types::{Float, PI, INV_PI},
+};
+use cgmath::prelude::*;
+use std::fs;
+use std::io;
+use std::sync::Arc;
+
+// Resolution of the MERL tables along $\theta_h$, $\theta_d$ and $\phi_d$
+const MERL_THETA_H: usize = 90;
+const MERL_THETA_D: usize = 90;
+const MERL_PHI_D: usize = 180;
+
+// MERL data is stored in per-channel units, these bring it to reflectance
+const MERL_SCALE: [f64; 3] = [1.0 / 1500.0, 1.15 / 1500.0, 1.66 / 1500.0];
+
+// Resolution of the tabulated half vector distribution used for importance sampling
+const SAMPLING_THETA_H: usize = 90;
+const SAMPLING_PHI_H: usize = 64;
+const SAMPLING_THETA_D: usize = 16;
+const SAMPLING_PHI_D: usize = 16;
+
+// BRDF tabulated over Rusinkiewicz half/difference angles. Isotropic data has a single
+// $\phi_h$ entry and stores $\phi_d$ over $[0,\pi)$ only, anisotropic data stores both azimuths
+// over $[0,2\pi)$.
+struct BRDFTable {
+    n_theta_h: usize,
+    n_phi_h: usize,
+    n_theta_d: usize,
+    n_phi_d: usize,
+    // MERL tables sample $\theta_h$ densely near the normal using a square root mapping
+    warp_theta_h: bool,
+    values: Vec<Spectrum>,
+}
+
+pub struct MeasuredBRDF {
+    table: BRDFTable,
+    distribution: Distribution2D,
+}
+
+impl MeasuredBRDF {
+    // _values_ are indexed by $\theta_h$, $\phi_h$, $\theta_d$ and $\phi_d$, with $\phi_d$ varying fastest
+    pub fn new(n_theta_h: usize, n_phi_h: usize, n_theta_d: usize, n_phi_d: usize, values: Vec<Spectrum>) -> MeasuredBRDF {
+        MeasuredBRDF::from_table(BRDFTable { n_theta_h, n_phi_h, n_theta_d, n_phi_d, warp_theta_h: false, values })
+    }
+
+    fn from_table(table: BRDFTable) -> MeasuredBRDF {
+        assert!(table.n_theta_h > 0 && table.n_phi_h > 0 && table.n_theta_d > 0 && table.n_phi_d > 0);
+        assert_eq!(table.values.len(), table.n_theta_h * table.n_phi_h * table.n_theta_d * table.n_phi_d);
+
+        let distribution = table.half_vector_distribution();
+        MeasuredBRDF { table, distribution }
+    }
+
+    // Read an isotropic BRDF in the binary format of the MERL database
+    pub fn read_merl(path: &str) -> io::Result<MeasuredBRDF> {
+        MeasuredBRDF::from_merl_bytes(&fs::read(path)?)
+    }
+
+    pub fn from_merl_bytes(data: &[u8]) -> io::Result<MeasuredBRDF> {
+        let dims = read_dimensions(data, 3)?;
+        if dims != [MERL_THETA_H, MERL_THETA_D, MERL_PHI_D] {
+            return Err(invalid_data("unexpected dimensions in MERL file"));
+        }
+
+        let n = MERL_THETA_H * MERL_THETA_D * MERL_PHI_D;
+        let channels = read_channels(&data[12..], n, MERL_SCALE)?;
+        Ok(MeasuredBRDF::from_table(BRDFTable {
+            n_theta_h: MERL_THETA_H,
+            n_phi_h: 1,
+            n_theta_d: MERL_THETA_D,
+            n_phi_d: MERL_PHI_D,
+            warp_theta_h: true,
+            values: channels,
+        }))
+    }
+
+    // Read tabulated anisotropic data laid out like a MERL file: four 32-bit dimensions for
+    // $\theta_h$, $\phi_h$, $\theta_d$ and $\phi_d$ followed by a block of doubles per channel,
+    // all angles sampled uniformly
+    pub fn read_tabulated(path: &str) -> io::Result<MeasuredBRDF> {
+        MeasuredBRDF::from_bytes(&fs::read(path)?)
+    }
+
+    // Tabulated data in the layout read by _read_tabulated_
+    pub fn from_bytes(data: &[u8]) -> io::Result<MeasuredBRDF> {
+        let dims = read_dimensions(data, 4)?;
+        if dims.contains(&0) {
+            return Err(invalid_data("empty dimension in tabulated BRDF"));
+        }
+
+        let n = dims.iter().try_fold(1usize, |n, &d| n.checked_mul(d))
+            .ok_or_else(|| invalid_data("tabulated BRDF too large"))?;
+        let channels = read_channels(&data[16..], n, [1.0; 3])?;
+        Ok(MeasuredBRDF::new(dims[0], dims[1], dims[2], dims[3], channels))
+    }
+
+    // Density of sampling _wh_ in the upper hemisphere with respect to solid angle
+    fn half_vector_pdf(&self, wh: &Vector3f) -> Float {
+        let theta_h = spherical_theta(wh);
+        let sin_theta_h = theta_h.sin();
+        if sin_theta_h == 0.0 {
+            return 0.0;
+        }
+        let pdf = self.distribution.pdf(spherical_phi(wh) * (0.5 * INV_PI), theta_h / (PI / 2.0));
+        pdf / (PI * PI * sin_theta_h)
+    }
+}
+
+impl BRDFTable {
+    fn is_isotropic(&self) -> bool {
+        self.n_phi_h == 1
+    }
+
+    // Nearest table entry for the given half and difference angles
+    fn lookup(&self, theta_h: Float, phi_h: Float, theta_d: Float, phi_d: Float) -> Spectrum {
+        let th = theta_h.max(0.0) / (PI / 2.0);
+        let th = if self.warp_theta_h { th.sqrt() } else { th };
+        let phi_d_range = if self.is_isotropic() { PI } else { 2.0 * PI };
+
+        let ith = table_index(th, self.n_theta_h);
+        let iph = table_index(phi_h.rem_euclid(2.0 * PI) / (2.0 * PI), self.n_phi_h);
+        let itd = table_index(theta_d / (PI / 2.0), self.n_theta_d);
+        let ipd = table_index(phi_d.rem_euclid(phi_d_range) / phi_d_range, self.n_phi_d);
+
+        self.values[((ith * self.n_phi_h + iph) * self.n_theta_d + itd) * self.n_phi_d + ipd]
+    }
+
+    // Tabulate the BRDF averaged over difference angles as a function of the half vector
+    fn half_vector_distribution(&self) -> Distribution2D {
+        let nu = if self.is_isotropic() { 1 } else { SAMPLING_PHI_H };
+        let nv = SAMPLING_THETA_H;
+        let mut func = Vec::with_capacity(nu * nv);
+        for v in 0..nv {
+            let theta_h = (v as Float + 0.5) / nv as Float * (PI / 2.0);
+            for u in 0..nu {
+                let phi_h = (u as Float + 0.5) / nu as Float * (2.0 * PI);
+                let mut sum = 0.0;
+                for i in 0..SAMPLING_THETA_D * SAMPLING_PHI_D {
+                    let theta_d = ((i / SAMPLING_PHI_D) as Float + 0.5) / SAMPLING_THETA_D as Float * (PI / 2.0);
+                    let phi_d = ((i % SAMPLING_PHI_D) as Float + 0.5) / SAMPLING_PHI_D as Float * (2.0 * PI);
+                    sum += self.lookup(theta_h, phi_h, theta_d, phi_d).y().max(0.0);
+                }
+                func.push(sum * theta_h.cos() * theta_h.sin());
+            }
+        }
+        Distribution2D::new(&func, nu, nv)
+    }
+}
+
+#[inline]
+fn table_index(x: Float, n: usize) -> usize {
+    ((x * n as Float).max(0.0) as usize).min(n - 1)
+}
+
+// Rotate _v_ by _angle_ around the normalized _axis_
+fn rotate_vector(v: &Vector3f, axis: &Vector3f, angle: Float) -> Vector3f {
+    let (sin_angle, cos_angle) = angle.sin_cos();
+    v * cos_angle + axis * (axis.dot(*v) * (1.0 - cos_angle)) + axis.cross(*v) * sin_angle
+}
+
+fn read_dimensions(data: &[u8], n: usize) -> io::Result<Vec<usize>> {
+    if data.len() < 4 * n {
+        return Err(invalid_data("truncated BRDF header"));
+    }
+    Ok(data[..4 * n].chunks(4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]).max(0) as usize).collect())
+}
+
+// Read _n_ doubles for each of the three channels, negative values mark missing measurements
+fn read_channels(data: &[u8], n: usize, scale: [f64; 3]) -> io::Result<Vec<Spectrum>> {
+    let size = n.checked_mul(3 * 8).ok_or_else(|| invalid_data("BRDF data too large"))?;
+    if data.len() < size {
+        return Err(invalid_data("truncated BRDF data"));
+    }
+    let value = |c: usize, i: usize| {
+        let o = 8 * (c * n + i);
+        let mut b = [0u8; 8];
+        b.copy_from_slice(&data[o..o + 8]);
+        (f64::from_le_bytes(b) * scale[c]).max(0.0) as Float
+    };
+    Ok((0..n).map(|i| Spectrum::new(value(0, i), value(1, i), value(2, i))).collect())
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+pub struct MeasuredBxDF {
+    brdf: Arc<MeasuredBRDF>,
+}
+
+impl MeasuredBxDF {
+    pub fn new(brdf: Arc<MeasuredBRDF>) -> MeasuredBxDF {
+        MeasuredBxDF { brdf }
+    }
+}
+
+impl BxDF for MeasuredBxDF {
+    fn f(&self, wo: &Vector3f, wi: &Vector3f) -> Spectrum {
+        if !same_hemisphere(wo, wi) {
+            return Spectrum::black();
+        }
+
+        // Measurements only cover the upper hemisphere, mirror directions below it
+        let (wo, wi) = if wo.z < 0.0 { (-*wo, -*wi) } else { (*wo, *wi) };
+
+        // Compute half and difference angles for _wo_ and _wi_
+        let wh = wo + wi;
+        if wh.magnitude2() == 0.0 {
+            return Spectrum::black();
+        }
+        let wh = wh.normalize();
+        let theta_h = spherical_theta(&wh);
+        let phi_h = spherical_phi(&wh);
+        let wd = rotate_vector(&rotate_vector(&wi, &Vector3f::unit_z(), -phi_h), &Vector3f::unit_y(), -theta_h);
+
+        self.brdf.table.lookup(theta_h, phi_h, spherical_theta(&wd), spherical_phi(&wd))
+    }
+
+    fn sample_f(&self, wo: Vector3f, wi: &mut Vector3f, u1: Float, u2: Float) -> (Spectrum, Float) {
+        // Choose between cosine sampling and sampling the tabulated half vector distribution
+        if u1 < 0.5 {
+            *wi = cosine_sample_hemisphere(2.0 * u1, u2);
+            if wo.z < 0.0 {
+                wi.z *= -1.0;
+            }
+        } else {
+            let (uv, pdf) = self.brdf.distribution.sample_continuous(((u1 - 0.5) * 2.0).min(1.0 - Float::EPSILON), u2);
+            if pdf == 0.0 {
+                return (Spectrum::black(), 0.0);
+            }
+            let theta_h = uv[1] * (PI / 2.0);
+            let phi_h = uv[0] * (2.0 * PI);
+            let mut wh = spherical_direction(theta_h.sin(), theta_h.cos(), phi_h);
+            if wo.z < 0.0 {
+                wh = -wh;
+            }
+            *wi = reflect(&wo, &wh);
+            if !same_hemisphere(&wo, wi) {
+                return (Spectrum::black(), 0.0);
+            }
+        }
+
+        (self.f(&wo, wi), self.pdf(&wo, wi))
+    }
+
+    fn bxdf_type(&self) -> BxDFType {
+        BxDFType::BSDF_REFLECTION | BxDFType::BSDF_GLOSSY
+    }
+
+    fn pdf(&self, wo: &Vector3f, wi: &Vector3f) -> Float {
+        if !same_hemisphere(wo, wi) {
+            return 0.0;
+        }
+        let cosine_pdf = abs_cos_theta(wi) * INV_PI;
+
+        // Find density of the half vector on the upper hemisphere
+        let wh = wo + wi;
+        if wh.magnitude2() == 0.0 {
+            return 0.5 * cosine_pdf;
+        }
+        let wh = if wh.z < 0.0 { -wh.normalize() } else { wh.normalize() };
+        let half_pdf = self.brdf.half_vector_pdf(&wh) / (4.0 * wo.dot(wh).abs());
+
+        0.5 * (cosine_pdf + half_pdf)
+    }
+}
+
+pub struct MeasuredMaterial {
+    brdf: Arc<MeasuredBRDF>,
+    bump_map: Option<Arc<Texture<Float>>>,
+    normal_map: Option<Arc<NormalMap>>,
+}
+
+impl MeasuredMaterial {
+    pub fn new(brdf: Arc<MeasuredBRDF>,
+               bump_map: Option<Arc<Texture<Float>>>,
+               normal_map: Option<Arc<NormalMap>>) -> MeasuredMaterial {
+        MeasuredMaterial { brdf, bump_map, normal_map }
+    }
+}
+
+impl Material for MeasuredMaterial {
+    fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
+        let dgs = shading_geometry(&self.bump_map, &self.normal_map, dg_geom, dg_shading);
+
+        let mut bsdf = BSDF::new(dgs, dg_geom.nn);
+        bsdf.add(Box::new(MeasuredBxDF::new(self.brdf.clone())));
+        bsdf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tabulated(dims: &[i32], values: &[f64]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for d in dims {
+            data.extend_from_slice(&d.to_le_bytes());
+        }
+        for v in values {
+            data.extend_from_slice(&v.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn tabulated_round_trip() {
+        // Two $\theta_h$ and three $\phi_d$ entries, channel blocks in R, G, B order
+        let r = [0.1, 0.2, 0.3, 0.4, 0.5, 0.6];
+        let g = [1.1, 1.2, 1.3, 1.4, 1.5, 1.6];
+        let b = [2.1, 2.2, 2.3, 2.4, 2.5, 2.6];
+        let values: Vec<f64> = r.iter().chain(g.iter()).chain(b.iter()).cloned().collect();
+
+        let brdf = MeasuredBRDF::from_bytes(&tabulated(&[2, 1, 1, 3], &values)).unwrap();
+        let table = &brdf.table;
+        assert_eq!((table.n_theta_h, table.n_phi_h, table.n_theta_d, table.n_phi_d), (2, 1, 1, 3));
+        assert_eq!(table.values.len(), 6);
+        for i in 0..6 {
+            let v = table.values[i];
+            assert_eq!((v.r, v.g, v.b), (r[i] as Float, g[i] as Float, b[i] as Float));
+        }
+    }
+
+    #[test]
+    fn tabulated_rejects_bad_sizes() {
+        let truncated = MeasuredBRDF::from_bytes(&tabulated(&[2, 1, 1, 3], &[0.5; 17]));
+        assert_eq!(truncated.err().unwrap().kind(), io::ErrorKind::InvalidData);
+
+        let max = i32::MAX;
+        let overflow = MeasuredBRDF::from_bytes(&tabulated(&[max, max, max, max], &[]));
+        assert_eq!(overflow.err().unwrap().kind(), io::ErrorKind::InvalidData);
+    }
+}
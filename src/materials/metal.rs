@@ -1,38 +1,185 @@
 use std::sync::Arc;
 use core::{
     differential_geometry::DifferentialGeometry,
-    reflection::{Blinn, BSDF},
+    reflection::{BSDF, TrowbridgeReitz, roughness_to_alpha},
     spectrum::Spectrum,
     texture::Texture,
     types::Float,
-    material::{Material, bump},
+    material::{Material, NormalMap, shading_geometry},
 };
 use core::reflection::{FresnelConductor, Microfacet};
+use core::math::clamp;
+use textures::ConstantTexture;
+
+// Metals with built-in complex index of refraction. Values of $\eta$ and $k$ are measured data
+// from refractiveindex.info sampled at 650, 550 and 450 nm for the red, green and blue channels.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NamedMetal {
+    Aluminium,
+    Chromium,
+    Copper,
+    Gold,
+    Iron,
+    Nickel,
+    Platinum,
+    Silver,
+    Titanium,
+}
+
+impl NamedMetal {
+    // Look up a metal by chemical symbol, e.g. "Au"
+    pub fn from_symbol(symbol: &str) -> Option<NamedMetal> {
+        match symbol {
+            "Al" => Some(NamedMetal::Aluminium),
+            "Cr" => Some(NamedMetal::Chromium),
+            "Cu" => Some(NamedMetal::Copper),
+            "Au" => Some(NamedMetal::Gold),
+            "Fe" => Some(NamedMetal::Iron),
+            "Ni" => Some(NamedMetal::Nickel),
+            "Pt" => Some(NamedMetal::Platinum),
+            "Ag" => Some(NamedMetal::Silver),
+            "Ti" => Some(NamedMetal::Titanium),
+            _ => None,
+        }
+    }
+
+    pub fn eta(&self) -> Spectrum {
+        match *self {
+            NamedMetal::Aluminium => Spectrum::new(1.3456, 0.96521, 0.61722),
+            NamedMetal::Chromium => Spectrum::new(3.1071, 3.1812, 2.3230),
+            NamedMetal::Copper => Spectrum::new(0.27105, 0.67693, 1.3164),
+            NamedMetal::Gold => Spectrum::new(0.18299, 0.42108, 1.3734),
+            NamedMetal::Iron => Spectrum::new(2.9114, 2.9497, 2.5845),
+            NamedMetal::Nickel => Spectrum::new(2.3672, 1.6574, 1.4166),
+            NamedMetal::Platinum => Spectrum::new(2.3757, 2.0847, 1.8453),
+            NamedMetal::Silver => Spectrum::new(0.15943, 0.14512, 0.13547),
+            NamedMetal::Titanium => Spectrum::new(2.7407, 2.5418, 2.2670),
+        }
+    }
+
+    pub fn k(&self) -> Spectrum {
+        match *self {
+            NamedMetal::Aluminium => Spectrum::new(7.4746, 6.3995, 5.3031),
+            NamedMetal::Chromium => Spectrum::new(3.3314, 3.3291, 3.1350),
+            NamedMetal::Copper => Spectrum::new(3.6092, 2.6248, 2.2921),
+            NamedMetal::Gold => Spectrum::new(3.4242, 2.3459, 1.7704),
+            NamedMetal::Iron => Spectrum::new(3.0893, 2.9318, 2.7670),
+            NamedMetal::Nickel => Spectrum::new(4.4988, 3.0501, 2.3830),
+            NamedMetal::Platinum => Spectrum::new(4.2655, 3.7153, 3.1365),
+            NamedMetal::Silver => Spectrum::new(3.9291, 3.1900, 2.3808),
+            NamedMetal::Titanium => Spectrum::new(3.8143, 3.4345, 3.0385),
+        }
+    }
+}
+
+// Map reflectivity at normal incidence _r_ and edge tint _g_ to $\eta$ and $k$ (Gulbrandsen 2014)
+pub fn artistic_to_conductor(r: Spectrum, g: Spectrum) -> (Spectrum, Spectrum) {
+    let channel = |r: Float, g: Float| {
+        let r = clamp(r, 0.0, 0.99);
+        let g = clamp(g, 0.0, 1.0);
+        let sr = r.sqrt();
+        let n_min = (1.0 - r) / (1.0 + r);
+        let n_max = (1.0 + sr) / (1.0 - sr);
+        let n = g * n_min + (1.0 - g) * n_max;
+        let k2 = (r * (n + 1.0) * (n + 1.0) - (n - 1.0) * (n - 1.0)) / (1.0 - r);
+        (n, k2.max(0.0).sqrt())
+    };
+    let (er, kr) = channel(r.r, g.r);
+    let (eg, kg) = channel(r.g, g.g);
+    let (eb, kb) = channel(r.b, g.b);
+    (Spectrum::new(er, eg, eb), Spectrum::new(kr, kg, kb))
+}
+
+enum Conductor {
+    Physical { eta: Arc<Texture<Spectrum>>, k: Arc<Texture<Spectrum>> },
+    Artistic { reflectivity: Arc<Texture<Spectrum>>, edge_tint: Arc<Texture<Spectrum>> },
+}
+
+impl Conductor {
+    fn evaluate(&self, dg: &DifferentialGeometry) -> (Spectrum, Spectrum) {
+        match *self {
+            Conductor::Physical { ref eta, ref k } => (eta.evaluate(dg), k.evaluate(dg)),
+            Conductor::Artistic { ref reflectivity, ref edge_tint } => {
+                artistic_to_conductor(reflectivity.evaluate(dg), edge_tint.evaluate(dg))
+            }
+        }
+    }
+}
 
 pub struct MetalMaterial {
-    eta: Arc<Texture<Spectrum>>,
-    k: Arc<Texture<Spectrum>>,
-    roughness: Arc<Texture<Float>>,
+    conductor: Conductor,
+    u_roughness: Arc<Texture<Float>>,
+    v_roughness: Arc<Texture<Float>>,
+    remap_roughness: bool,
     bump_map: Option<Arc<Texture<Float>>>,
+    normal_map: Option<Arc<NormalMap>>,
 }
 
 impl MetalMaterial {
+    // _roughness_ is perceptual roughness when _remap_roughness_ is set and microfacet $\alpha$ otherwise
     pub fn new(eta: Arc<Texture<Spectrum>>,
                k: Arc<Texture<Spectrum>>,
                roughness: Arc<Texture<Float>>,
-               bump_map: Option<Arc<Texture<Float>>>) -> MetalMaterial {
-        MetalMaterial { eta, k, roughness, bump_map }
+               remap_roughness: bool,
+               bump_map: Option<Arc<Texture<Float>>>,
+               normal_map: Option<Arc<NormalMap>>) -> MetalMaterial {
+        MetalMaterial::new_anisotropic(eta, k, roughness.clone(), roughness, remap_roughness, bump_map, normal_map)
+    }
+
+    // Roughness may differ along $\dpdu$ and $\dpdv$ for brushed and machined metals
+    pub fn new_anisotropic(eta: Arc<Texture<Spectrum>>,
+                           k: Arc<Texture<Spectrum>>,
+                           u_roughness: Arc<Texture<Float>>,
+                           v_roughness: Arc<Texture<Float>>,
+                           remap_roughness: bool,
+                           bump_map: Option<Arc<Texture<Float>>>,
+                           normal_map: Option<Arc<NormalMap>>) -> MetalMaterial {
+        MetalMaterial { conductor: Conductor::Physical { eta, k }, u_roughness, v_roughness, remap_roughness, bump_map, normal_map }
+    }
+
+    pub fn new_named(metal: NamedMetal,
+                     roughness: Arc<Texture<Float>>,
+                     remap_roughness: bool,
+                     bump_map: Option<Arc<Texture<Float>>>,
+                     normal_map: Option<Arc<NormalMap>>) -> MetalMaterial {
+        let eta = Arc::new(ConstantTexture::new(metal.eta()));
+        let k = Arc::new(ConstantTexture::new(metal.k()));
+        MetalMaterial::new(eta, k, roughness, remap_roughness, bump_map, normal_map)
+    }
+
+    // Color of the metal facing the viewer is given by _reflectivity_, and the color it tends
+    // to at grazing angles by _edge_tint_
+    pub fn new_artistic(reflectivity: Arc<Texture<Spectrum>>,
+                        edge_tint: Arc<Texture<Spectrum>>,
+                        roughness: Arc<Texture<Float>>,
+                        remap_roughness: bool,
+                        bump_map: Option<Arc<Texture<Float>>>,
+                        normal_map: Option<Arc<NormalMap>>) -> MetalMaterial {
+        MetalMaterial {
+            conductor: Conductor::Artistic { reflectivity, edge_tint },
+            u_roughness: roughness.clone(),
+            v_roughness: roughness,
+            remap_roughness,
+            bump_map,
+            normal_map,
+        }
     }
 }
 
 impl Material for MetalMaterial {
     fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
-        let dgs = self.bump_map.as_ref().map_or_else(|| dg_shading.clone(), |b| bump(b.as_ref(), dg_geom, dg_shading));
+        let dgs = shading_geometry(&self.bump_map, &self.normal_map, dg_geom, dg_shading);
 
-        let rough = self.roughness.evaluate(&dgs);
+        let mut u_rough = self.u_roughness.evaluate(&dgs);
+        let mut v_rough = self.v_roughness.evaluate(&dgs);
+        if self.remap_roughness {
+            u_rough = roughness_to_alpha(u_rough);
+            v_rough = roughness_to_alpha(v_rough);
+        }
 
-        let md = Blinn::new(1.0 / rough);
-        let fr_mf = FresnelConductor::new(self.eta.evaluate(&dgs), self.k.evaluate(&dgs));
+        let md = TrowbridgeReitz::new(u_rough, v_rough);
+        let (eta, k) = self.conductor.evaluate(&dgs);
+        let fr_mf = FresnelConductor::new(eta, k);
 
         let mut bsdf = BSDF::new(dgs, dg_geom.nn);
         bsdf.add(Box::new(Microfacet::new(Spectrum::white(), Box::new(fr_mf), Box::new(md))));
@@ -1,6 +1,6 @@
 use core::{
     differential_geometry::DifferentialGeometry,
-    material::{Material, bump},
+    material::{Material, NormalMap, shading_geometry},
     reflection::{FresnelNoOp, BSDF, SpecularReflection},
     spectrum::Spectrum,
     texture::Texture,
@@ -12,18 +12,20 @@ use std::sync::Arc;
 pub struct MirrorMaterial {
     kr: Arc<Texture<Spectrum>>,
     bump_map: Option<Arc<Texture<Float>>>,
+    normal_map: Option<Arc<NormalMap>>,
 }
 
 impl MirrorMaterial {
     pub fn new(kr: Arc<Texture<Spectrum>>,
-               bump_map: Option<Arc<Texture<Float>>>) -> MirrorMaterial {
-        MirrorMaterial { kr, bump_map }
+               bump_map: Option<Arc<Texture<Float>>>,
+               normal_map: Option<Arc<NormalMap>>) -> MirrorMaterial {
+        MirrorMaterial { kr, bump_map, normal_map }
     }
 }
 
 impl Material for MirrorMaterial {
     fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
-        let dgs = self.bump_map.as_ref().map_or_else(|| dg_shading.clone(), |b| bump(b.as_ref(), dg_geom, dg_shading));
+        let dgs = shading_geometry(&self.bump_map, &self.normal_map, dg_geom, dg_shading);
 
         let r = self.kr.evaluate(&dgs).clamp_positive();
 
@@ -38,6 +40,6 @@ impl Material for MirrorMaterial {
 
 impl Default for MirrorMaterial {
     fn default() -> Self {
-        MirrorMaterial::new(Arc::new(ConstantTexture::new(Spectrum::from(0.9))), None)
+        MirrorMaterial::new(Arc::new(ConstantTexture::new(Spectrum::from(0.9))), None, None)
     }
 }
@@ -0,0 +1,82 @@
+use std::sync::Arc;
+use core::{
+    differential_geometry::DifferentialGeometry,
+    geometry::Point3f,
+    material::Material,
+    reflection::{BSDF, ScaledBxDF},
+    spectrum::Spectrum,
+    texture::Texture,
+    types::Float,
+};
+
+// Fraction of the second material in a _MixMaterial_
+pub enum MixAmount {
+    Float(Arc<Texture<Float>>),
+    Spectrum(Arc<Texture<Spectrum>>),
+}
+
+impl MixAmount {
+    fn evaluate(&self, dg: &DifferentialGeometry) -> Spectrum {
+        match *self {
+            MixAmount::Float(ref t) => Spectrum::from(t.evaluate(dg)),
+            MixAmount::Spectrum(ref t) => t.evaluate(dg),
+        }
+    }
+}
+
+// Blends two materials by _amount_. Linear blending combines the lobes of both BSDFs, stochastic
+// blending picks one of the materials per shading point with probability given by the amount.
+pub struct MixMaterial {
+    m1: Box<Material>,
+    m2: Box<Material>,
+    amount: MixAmount,
+    stochastic: bool,
+}
+
+impl MixMaterial {
+    pub fn new(m1: Box<Material>, m2: Box<Material>, amount: MixAmount, stochastic: bool) -> MixMaterial {
+        MixMaterial { m1, m2, amount, stochastic }
+    }
+}
+
+impl Material for MixMaterial {
+    fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
+        let s2 = self.amount.evaluate(dg_shading).clamp(0.0, 1.0);
+        let s1 = Spectrum::white() - s2;
+
+        if self.stochastic {
+            // Choose material with the average amount, rescaling for tinted masks
+            let p = (s2.r + s2.g + s2.b) / 3.0;
+            let (mut bsdf, s, prob) = if hash_point(&dg_shading.p) < p {
+                (self.m2.get_bsdf(dg_geom, dg_shading), s2, p)
+            } else {
+                (self.m1.get_bsdf(dg_geom, dg_shading), s1, 1.0 - p)
+            };
+            if s.r != s.g || s.g != s.b {
+                bsdf.scale(s / prob);
+            }
+            return bsdf;
+        }
+
+        // Combine scaled lobes of both materials in the shading frame of the first
+        let mut bsdf = self.m1.get_bsdf(dg_geom, dg_shading);
+        bsdf.scale(s1);
+        for bxdf in self.m2.get_bsdf(dg_geom, dg_shading).into_bxdfs() {
+            bsdf.add(Box::new(ScaledBxDF::new(bxdf, s2)));
+        }
+        bsdf
+    }
+}
+
+// Map a point to a pseudo-random number in $[0,1)$ so that choices are consistent at a location
+fn hash_point(p: &Point3f) -> Float {
+    let mut h: u64 = 0xcbf29ce484222325;
+    for c in &[p.x, p.y, p.z] {
+        h ^= c.to_bits() as u64;
+        h = h.wrapping_mul(0x100000001b3);
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51afd7ed558ccd);
+        h ^= h >> 33;
+    }
+    (h >> 40) as Float / (1u64 << 24) as Float
+}
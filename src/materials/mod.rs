@@ -1,9 +1,27 @@
+mod coated;
+mod disney;
 mod glass;
 mod matte;
+mod measured;
 mod metal;
 mod mirror;
+mod mix;
+mod plastic;
+mod substrate;
+mod thindielectric;
+mod translucent;
+mod uber;
 
+pub use self::coated::CoatedMaterial;
+pub use self::disney::{DisneyMaterial, DisneyParameters};
 pub use self::glass::GlassMaterial;
 pub use self::matte::MatteMaterial;
-pub use self::metal::MetalMaterial;
+pub use self::measured::{MeasuredMaterial, MeasuredBRDF};
+pub use self::metal::{MetalMaterial, NamedMetal, artistic_to_conductor};
 pub use self::mirror::MirrorMaterial;
+pub use self::mix::{MixMaterial, MixAmount};
+pub use self::plastic::PlasticMaterial;
+pub use self::substrate::SubstrateMaterial;
+pub use self::thindielectric::ThinDielectricMaterial;
+pub use self::translucent::TranslucentMaterial;
+pub use self::uber::UberMaterial;
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+use core::{
+    differential_geometry::DifferentialGeometry,
+    material::{Material, NormalMap, shading_geometry},
+    reflection::{BSDF, Lambertian, Microfacet, FresnelDielectric, TrowbridgeReitz, roughness_to_alpha},
+    spectrum::Spectrum,
+    texture::Texture,
+    types::Float,
+};
+
+pub struct PlasticMaterial {
+    kd: Arc<Texture<Spectrum>>,
+    ks: Arc<Texture<Spectrum>>,
+    roughness: Arc<Texture<Float>>,
+    remap_roughness: bool,
+    bump_map: Option<Arc<Texture<Float>>>,
+    normal_map: Option<Arc<NormalMap>>,
+}
+
+impl PlasticMaterial {
+    // _roughness_ is perceptual roughness when _remap_roughness_ is set and microfacet $\alpha$ otherwise
+    pub fn new(kd: Arc<Texture<Spectrum>>,
+               ks: Arc<Texture<Spectrum>>,
+               roughness: Arc<Texture<Float>>,
+               remap_roughness: bool,
+               bump_map: Option<Arc<Texture<Float>>>,
+               normal_map: Option<Arc<NormalMap>>) -> PlasticMaterial {
+        PlasticMaterial { kd, ks, roughness, remap_roughness, bump_map, normal_map }
+    }
+}
+
+impl Material for PlasticMaterial {
+    fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
+        let dgs = shading_geometry(&self.bump_map, &self.normal_map, dg_geom, dg_shading);
+
+        // Initialize diffuse component of plastic material
+        let kd = self.kd.evaluate(&dgs).clamp_positive();
+        let ks = self.ks.evaluate(&dgs).clamp_positive();
+        let mut rough = self.roughness.evaluate(&dgs);
+        if self.remap_roughness {
+            rough = roughness_to_alpha(rough);
+        }
+
+        let mut bsdf = BSDF::new(dgs, dg_geom.nn);
+        if !kd.is_black() {
+            bsdf.add(Box::new(Lambertian::new(kd)));
+        }
+
+        // Initialize specular component of plastic material
+        if !ks.is_black() {
+            let fresnel = FresnelDielectric::new(1.5, 1.0);
+            let distribution = TrowbridgeReitz::new(rough, rough);
+            bsdf.add(Box::new(Microfacet::new(ks, Box::new(fresnel), Box::new(distribution))));
+        }
+
+        bsdf
+    }
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+use core::{
+    differential_geometry::DifferentialGeometry,
+    material::{Material, NormalMap, shading_geometry},
+    reflection::{BSDF, FresnelBlend, TrowbridgeReitz, roughness_to_alpha},
+    spectrum::Spectrum,
+    texture::Texture,
+    types::Float,
+};
+
+pub struct SubstrateMaterial {
+    kd: Arc<Texture<Spectrum>>,
+    ks: Arc<Texture<Spectrum>>,
+    u_roughness: Arc<Texture<Float>>,
+    v_roughness: Arc<Texture<Float>>,
+    remap_roughness: bool,
+    bump_map: Option<Arc<Texture<Float>>>,
+    normal_map: Option<Arc<NormalMap>>,
+}
+
+impl SubstrateMaterial {
+    // Roughness is perceptual when _remap_roughness_ is set and microfacet $\alpha$ otherwise
+    pub fn new(kd: Arc<Texture<Spectrum>>,
+               ks: Arc<Texture<Spectrum>>,
+               u_roughness: Arc<Texture<Float>>,
+               v_roughness: Arc<Texture<Float>>,
+               remap_roughness: bool,
+               bump_map: Option<Arc<Texture<Float>>>,
+               normal_map: Option<Arc<NormalMap>>) -> SubstrateMaterial {
+        SubstrateMaterial { kd, ks, u_roughness, v_roughness, remap_roughness, bump_map, normal_map }
+    }
+}
+
+impl Material for SubstrateMaterial {
+    fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
+        let dgs = shading_geometry(&self.bump_map, &self.normal_map, dg_geom, dg_shading);
+
+        let d = self.kd.evaluate(&dgs).clamp_positive();
+        let s = self.ks.evaluate(&dgs).clamp_positive();
+        let mut u_rough = self.u_roughness.evaluate(&dgs);
+        let mut v_rough = self.v_roughness.evaluate(&dgs);
+        if self.remap_roughness {
+            u_rough = roughness_to_alpha(u_rough);
+            v_rough = roughness_to_alpha(v_rough);
+        }
+
+        let mut bsdf = BSDF::new(dgs, dg_geom.nn);
+        if !d.is_black() || !s.is_black() {
+            let distribution = TrowbridgeReitz::new(u_rough, v_rough);
+            bsdf.add(Box::new(FresnelBlend::new(d, s, Box::new(distribution))));
+        }
+
+        bsdf
+    }
+}
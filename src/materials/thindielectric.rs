@@ -0,0 +1,59 @@
+use std::sync::Arc;
+use core::{
+    differential_geometry::DifferentialGeometry,
+    material::{Material, NormalMap, shading_geometry},
+    reflection::{BSDF, Fresnel, FresnelThinDielectric, FresnelThinFilm, SpecularReflection, ThinDielectricTransmission},
+    spectrum::Spectrum,
+    texture::Texture,
+    types::Float,
+};
+
+pub struct ThinDielectricMaterial {
+    kr: Arc<Texture<Spectrum>>,
+    kt: Arc<Texture<Spectrum>>,
+    index: Arc<Texture<Float>>,
+    film_thickness: Option<Arc<Texture<Float>>>,
+    bump_map: Option<Arc<Texture<Float>>>,
+    normal_map: Option<Arc<NormalMap>>,
+}
+
+impl ThinDielectricMaterial {
+    // Without _film_thickness_ the surface is a slab thick enough for its internal reflections to add
+    // incoherently, like a windowpane; with it, a film of that many nanometers showing interference
+    // colors, like a soap bubble
+    pub fn new(kr: Arc<Texture<Spectrum>>,
+               kt: Arc<Texture<Spectrum>>,
+               index: Arc<Texture<Float>>,
+               film_thickness: Option<Arc<Texture<Float>>>,
+               bump_map: Option<Arc<Texture<Float>>>,
+               normal_map: Option<Arc<NormalMap>>) -> ThinDielectricMaterial {
+        ThinDielectricMaterial { kr, kt, index, film_thickness, bump_map, normal_map }
+    }
+
+    fn fresnel(&self, dg: &DifferentialGeometry) -> Box<Fresnel> {
+        let eta = self.index.evaluate(dg);
+        match self.film_thickness {
+            Some(ref thickness) => Box::new(FresnelThinFilm::new(thickness.evaluate(dg), eta, 1.0, 1.0)),
+            None => Box::new(FresnelThinDielectric::new(eta)),
+        }
+    }
+}
+
+impl Material for ThinDielectricMaterial {
+    fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
+        let dgs = shading_geometry(&self.bump_map, &self.normal_map, dg_geom, dg_shading);
+
+        let r = self.kr.evaluate(&dgs).clamp_positive();
+        let t = self.kt.evaluate(&dgs).clamp_positive();
+
+        let mut bsdf = BSDF::new(dgs.clone(), dg_geom.nn);
+        if !r.is_black() {
+            bsdf.add(Box::new(SpecularReflection::new(r, self.fresnel(&dgs))));
+        }
+        if !t.is_black() {
+            bsdf.add(Box::new(ThinDielectricTransmission::new(t, self.fresnel(&dgs))));
+        }
+
+        bsdf
+    }
+}
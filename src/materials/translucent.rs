@@ -0,0 +1,83 @@
+use std::sync::Arc;
+use core::{
+    differential_geometry::DifferentialGeometry,
+    material::{Material, NormalMap, shading_geometry},
+    reflection::{BSDF, Lambertian, LambertianTransmission, Microfacet, MicrofacetTransmission},
+    reflection::{FresnelDielectric, TrowbridgeReitz, roughness_to_alpha},
+    spectrum::Spectrum,
+    texture::Texture,
+    types::Float,
+};
+
+// Index of refraction assumed for the glossy lobes of the thin surface
+const TRANSLUCENT_ETA: Float = 1.5;
+
+pub struct TranslucentMaterial {
+    kd: Arc<Texture<Spectrum>>,
+    ks: Arc<Texture<Spectrum>>,
+    roughness: Arc<Texture<Float>>,
+    reflect: Arc<Texture<Spectrum>>,
+    transmit: Arc<Texture<Spectrum>>,
+    remap_roughness: bool,
+    bump_map: Option<Arc<Texture<Float>>>,
+    normal_map: Option<Arc<NormalMap>>,
+}
+
+impl TranslucentMaterial {
+    // _reflect_ and _transmit_ split both the diffuse _kd_ and glossy _ks_ terms between the
+    // two sides of the surface, a black _ks_ leaves only the diffuse lobes
+    pub fn new(kd: Arc<Texture<Spectrum>>,
+               ks: Arc<Texture<Spectrum>>,
+               roughness: Arc<Texture<Float>>,
+               reflect: Arc<Texture<Spectrum>>,
+               transmit: Arc<Texture<Spectrum>>,
+               remap_roughness: bool,
+               bump_map: Option<Arc<Texture<Float>>>,
+               normal_map: Option<Arc<NormalMap>>) -> TranslucentMaterial {
+        TranslucentMaterial { kd, ks, roughness, reflect, transmit, remap_roughness, bump_map, normal_map }
+    }
+}
+
+impl Material for TranslucentMaterial {
+    fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
+        let dgs = shading_geometry(&self.bump_map, &self.normal_map, dg_geom, dg_shading);
+
+        let r = self.reflect.evaluate(&dgs).clamp_positive();
+        let t = self.transmit.evaluate(&dgs).clamp_positive();
+        let mut bsdf = BSDF::new_with_eta(dgs.clone(), dg_geom.nn, TRANSLUCENT_ETA);
+        if r.is_black() && t.is_black() {
+            return bsdf;
+        }
+
+        // Initialize diffuse reflection and transmission
+        let kd = self.kd.evaluate(&dgs).clamp_positive();
+        if !kd.is_black() {
+            if !r.is_black() {
+                bsdf.add(Box::new(Lambertian::new(r * kd)));
+            }
+            if !t.is_black() {
+                bsdf.add(Box::new(LambertianTransmission::new(t * kd)));
+            }
+        }
+
+        // Initialize glossy reflection and transmission
+        let ks = self.ks.evaluate(&dgs).clamp_positive();
+        if !ks.is_black() {
+            let mut rough = self.roughness.evaluate(&dgs);
+            if self.remap_roughness {
+                rough = roughness_to_alpha(rough);
+            }
+            if !r.is_black() {
+                let fresnel = FresnelDielectric::new(1.0, TRANSLUCENT_ETA);
+                let distribution = TrowbridgeReitz::new(rough, rough);
+                bsdf.add(Box::new(Microfacet::new(r * ks, Box::new(fresnel), Box::new(distribution))));
+            }
+            if !t.is_black() {
+                let distribution = TrowbridgeReitz::new(rough, rough);
+                bsdf.add(Box::new(MicrofacetTransmission::new(t * ks, Box::new(distribution), 1.0, TRANSLUCENT_ETA)));
+            }
+        }
+
+        bsdf
+    }
+}
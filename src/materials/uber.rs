@@ -0,0 +1,79 @@
+use std::sync::Arc;
+use core::{
+    differential_geometry::DifferentialGeometry,
+    material::{Material, NormalMap, shading_geometry},
+    reflection::{BSDF, FresnelDielectric, Lambertian, Microfacet, SpecularReflection, SpecularTransmission},
+    reflection::{TrowbridgeReitz, roughness_to_alpha},
+    spectrum::Spectrum,
+    texture::Texture,
+    types::Float,
+};
+
+pub struct UberMaterial {
+    kd: Arc<Texture<Spectrum>>,
+    ks: Arc<Texture<Spectrum>>,
+    kr: Arc<Texture<Spectrum>>,
+    kt: Arc<Texture<Spectrum>>,
+    roughness: Arc<Texture<Float>>,
+    index: Arc<Texture<Float>>,
+    opacity: Arc<Texture<Spectrum>>,
+    remap_roughness: bool,
+    bump_map: Option<Arc<Texture<Float>>>,
+    normal_map: Option<Arc<NormalMap>>,
+}
+
+impl UberMaterial {
+    pub fn new(kd: Arc<Texture<Spectrum>>,
+               ks: Arc<Texture<Spectrum>>,
+               kr: Arc<Texture<Spectrum>>,
+               kt: Arc<Texture<Spectrum>>,
+               roughness: Arc<Texture<Float>>,
+               index: Arc<Texture<Float>>,
+               opacity: Arc<Texture<Spectrum>>,
+               remap_roughness: bool,
+               bump_map: Option<Arc<Texture<Float>>>,
+               normal_map: Option<Arc<NormalMap>>) -> UberMaterial {
+        UberMaterial { kd, ks, kr, kt, roughness, index, opacity, remap_roughness, bump_map, normal_map }
+    }
+}
+
+impl Material for UberMaterial {
+    fn get_bsdf<'a>(&self, dg_geom: &DifferentialGeometry<'a>, dg_shading: &DifferentialGeometry<'a>) -> BSDF<'a> {
+        let dgs = shading_geometry(&self.bump_map, &self.normal_map, dg_geom, dg_shading);
+
+        let e = self.index.evaluate(&dgs);
+        let op = self.opacity.evaluate(&dgs).clamp(0.0, 1.0);
+        let kd = op * self.kd.evaluate(&dgs).clamp_positive();
+        let ks = op * self.ks.evaluate(&dgs).clamp_positive();
+        let kr = op * self.kr.evaluate(&dgs).clamp_positive();
+        let kt = op * self.kt.evaluate(&dgs).clamp_positive();
+        let mut rough = self.roughness.evaluate(&dgs);
+        if self.remap_roughness {
+            rough = roughness_to_alpha(rough);
+        }
+
+        let mut bsdf = BSDF::new_with_eta(dgs, dg_geom.nn, e);
+
+        // Let light pass straight through the transparent part of the surface
+        let transparency = Spectrum::white() - op;
+        if !transparency.is_black() {
+            bsdf.add(Box::new(SpecularTransmission::new(transparency, 1.0, 1.0)));
+        }
+
+        if !kd.is_black() {
+            bsdf.add(Box::new(Lambertian::new(kd)));
+        }
+        if !ks.is_black() {
+            let distribution = TrowbridgeReitz::new(rough, rough);
+            bsdf.add(Box::new(Microfacet::new(ks, Box::new(FresnelDielectric::new(e, 1.0)), Box::new(distribution))));
+        }
+        if !kr.is_black() {
+            bsdf.add(Box::new(SpecularReflection::new(kr, Box::new(FresnelDielectric::new(e, 1.0)))));
+        }
+        if !kt.is_black() {
+            bsdf.add(Box::new(SpecularTransmission::new(kt, e, 1.0)));
+        }
+
+        bsdf
+    }
+}
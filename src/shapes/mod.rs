@@ -1,3 +1,5 @@
 mod sphere;
+mod trianglemesh;
 
-pub use self::sphere::Sphere;
\ No newline at end of file
+pub use self::sphere::Sphere;
+pub use self::trianglemesh::{Triangle, TriangleMesh};
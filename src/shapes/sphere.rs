@@ -1,15 +1,16 @@
 use core::{
     differential_geometry::DifferentialGeometry,
-    geometry::{Ray, Normal},
+    geometry::{Ray, Normal, coordinate_system, distance_squared},
     math::{clamp, radians, solve_quadratic},
-    shape::Shape,
+    shape::{Shape, shape_pdf},
     transform::Transform,
-    types::{Float, PI},
+    types::{Float, PI, INFINITY},
 };
 use cgmath::{prelude::*, vec3};
 use core::geometry::Vector3f;
 use core::geometry::Point3f;
-use core::montecarlo::uniform_sample_sphere;
+use core::geometry::BBox;
+use core::montecarlo::{uniform_sample_sphere, uniform_sample_cone, uniform_cone_pdf};
 
 #[derive(Debug)]
 pub struct Sphere {
@@ -170,58 +171,58 @@ impl Shape for Sphere {
     }
 
     fn pdf(&self, p: &Point3f, wi: &Vector3f) -> Float {
-        unimplemented!()
-        /*
-        let Pcenter = (*ObjectToWorld)(Point(0,0,0));
+        let p_center = self.object_to_world.transform_point(Point3f::new(0.0, 0.0, 0.0));
+
         // Return uniform weight if point inside sphere
-        if (distance_squared(p, Pcenter) - radius*radius < 1e-4)
-        return Shape::Pdf(p, wi);
+        if distance_squared(p, &p_center) - self.radius * self.radius < 1e-4 {
+            return shape_pdf(self, p, wi);
+        }
 
         // Compute general sphere weight
-        float sinThetaMax2 = radius*radius / DistanceSquared(p, Pcenter);
-        float cosThetaMax = sqrtf(max(0.f, 1.f - sinThetaMax2));
-        return UniformConePdf(cosThetaMax);
-        */
+        let sin_theta_max2 = self.radius * self.radius / distance_squared(p, &p_center);
+        let cos_theta_max = (1.0 - sin_theta_max2).max(0.0).sqrt();
+        uniform_cone_pdf(cos_theta_max)
     }
 
     fn get_object_to_world(&self) -> &Transform {
         &self.object_to_world
     }
 
+    fn object_bound(&self) -> BBox {
+        BBox::new(&Point3f::new(-self.radius, -self.radius, self.zmin),
+                  Point3f::new(self.radius, self.radius, self.zmax))
+    }
+
     fn area(&self) -> Float {
         self.phi_max * self.radius * (self.zmax - self.zmin)
     }
 
     fn sample_point(&self, p: &Point3f, u1: Float, u2: Float) -> (Point3f, Normal) {
-        /*
-        Point Sphere::Sample(const Point &p, float u1, float u2,
-                     Normal *ns) const {
-    // Compute coordinate system for sphere sampling
-    Point Pcenter = (*ObjectToWorld)(Point(0,0,0));
-    Vector wc = Normalize(Pcenter - p);
-    Vector wcX, wcY;
-    CoordinateSystem(wc, &wcX, &wcY);
-
-    // Sample uniformly on sphere if $\pt{}$ is inside it
-    if (DistanceSquared(p, Pcenter) - radius*radius < 1e-4f)
-        return Sample(u1, u2, ns);
-
-    // Sample sphere uniformly inside subtended cone
-    float sinThetaMax2 = radius*radius / DistanceSquared(p, Pcenter);
-    float cosThetaMax = sqrtf(max(0.f, 1.f - sinThetaMax2));
-    DifferentialGeometry dgSphere;
-    float thit, rayEpsilon;
-    Point ps;
-    Ray r(p, UniformSampleCone(u1, u2, cosThetaMax, wcX, wcY, wc), 1e-3f);
-    if (!Intersect(r, &thit, &rayEpsilon, &dgSphere))
-        thit = Dot(Pcenter - p, Normalize(r.d));
-    ps = r(thit);
-    *ns = Normal(Normalize(ps - Pcenter));
-    if (ReverseOrientation) *ns *= -1.f;
-    return ps;
-}
-*/
-        unimplemented!()
+        // Compute coordinate system for sphere sampling
+        let p_center = self.object_to_world.transform_point(Point3f::new(0.0, 0.0, 0.0));
+        let wc = (p_center - p).normalize();
+        let (wc_x, wc_y) = coordinate_system(&wc);
+
+        // Sample uniformly on sphere if $\pt{}$ is inside it
+        if distance_squared(p, &p_center) - self.radius * self.radius < 1e-4 {
+            return self.sample(u1, u2);
+        }
+
+        // Sample sphere uniformly inside subtended cone
+        let sin_theta_max2 = self.radius * self.radius / distance_squared(p, &p_center);
+        let cos_theta_max = (1.0 - sin_theta_max2).max(0.0).sqrt();
+        let r = Ray::new(*p, uniform_sample_cone(u1, u2, cos_theta_max, &wc_x, &wc_y, &wc), 1e-3, INFINITY, 0.0);
+        let thit = match self.intersect(&r) {
+            Some((_, thit, _)) => thit,
+            None => (p_center - p).dot(r.d.normalize()),
+        };
+        let ps = r.point_at(thit);
+        let mut ns = Normal::from((ps - p_center).normalize());
+        if self.reverse_orientation {
+            ns *= -1.0;
+        }
+
+        (ps, ns)
     }
 
     fn sample(&self, u1: Float, u2: Float) -> (Point3f, Normal) {
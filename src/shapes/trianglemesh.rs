@@ -0,0 +1,310 @@
+use core::{
+    differential_geometry::DifferentialGeometry,
+    geometry::{Ray, Normal, BBox, coordinate_system},
+    montecarlo::{uniform_sample_triangle, uniform_sample_spherical_triangle, spherical_triangle_angles},
+    shape::{Shape, shape_pdf},
+    transform::Transform,
+    types::{Float, INFINITY, PI},
+};
+use cgmath::prelude::*;
+use core::geometry::Vector3f;
+use core::geometry::Point3f;
+use std::sync::Arc;
+
+// Solid angles outside this range are sampled by area, small triangles are well handled by
+// area sampling and spherical sampling loses precision for very large ones
+const MIN_SPHERICAL_SAMPLE_AREA: Float = 3e-4;
+const MAX_SPHERICAL_SAMPLE_AREA: Float = 6.22;
+
+#[derive(Debug)]
+struct TriangleMeshData {
+    object_to_world: Transform,
+    reverse_orientation: bool,
+    transform_swaps_handedness: bool,
+    vertex_indices: Vec<usize>,
+    p: Vec<Point3f>,
+    uvs: Option<Vec<[Float; 2]>>,
+}
+
+#[derive(Debug)]
+pub struct TriangleMesh {
+    world_to_object: Transform,
+    data: Arc<TriangleMeshData>,
+    triangles: Vec<Triangle>,
+}
+
+impl TriangleMesh {
+    // _vertex_indices_ holds three indices into _p_ per triangle, _p_ is given in object space
+    pub fn new(object_to_world: Transform,
+               world_to_object: Transform,
+               reverse_orientation: bool,
+               vertex_indices: Vec<usize>,
+               p: Vec<Point3f>,
+               uvs: Option<Vec<[Float; 2]>>) -> TriangleMesh {
+        assert_eq!(vertex_indices.len() % 3, 0);
+        assert!(uvs.as_ref().is_none_or(|uv| uv.len() == p.len()));
+
+        // Transform mesh vertices to world space
+        let p = p.iter().map(|pt| object_to_world.transform_point(*pt)).collect();
+        let transform_swaps_handedness = object_to_world.swaps_handedness();
+
+        let data = Arc::new(TriangleMeshData {
+            object_to_world,
+            reverse_orientation,
+            transform_swaps_handedness,
+            vertex_indices,
+            p,
+            uvs,
+        });
+        let triangles = (0..data.vertex_indices.len() / 3)
+            .map(|i| Triangle { mesh: data.clone(), v: 3 * i })
+            .collect();
+
+        TriangleMesh { world_to_object, data, triangles }
+    }
+
+    pub fn triangles(&self) -> &[Triangle] {
+        &self.triangles
+    }
+}
+
+impl Shape for TriangleMesh {
+    // Meshes are refined for light sampling but primitives intersect them as a whole
+    fn intersect(&self, ray: &Ray) -> Option<(DifferentialGeometry<'_>, Float, Float)> {
+        let mut r = ray.clone();
+        let mut closest = None;
+        for triangle in &self.triangles {
+            if let Some((dg, thit, ray_epsilon)) = triangle.intersect(&r) {
+                r.maxt = thit;
+                closest = Some((dg, thit, ray_epsilon));
+            }
+        }
+        closest
+    }
+
+    fn intersect_p(&self, ray: &Ray) -> bool {
+        self.triangles.iter().any(|t| t.intersect_p(ray))
+    }
+
+    // Density of choosing a triangle by area and sampling it from _p_, as _ShapeSet_ does
+    fn pdf(&self, p: &Point3f, wi: &Vector3f) -> Float {
+        let area = self.area();
+        if area == 0.0 {
+            return 0.0;
+        }
+        self.triangles.iter().map(|t| t.area() / area * t.pdf(p, wi)).sum()
+    }
+
+    fn get_object_to_world(&self) -> &Transform {
+        &self.data.object_to_world
+    }
+
+    fn object_bound(&self) -> BBox {
+        self.data.p.iter().fold(BBox::empty(), |b, p| b.union_point(&self.world_to_object.transform_point(*p)))
+    }
+
+    fn world_bound(&self) -> BBox {
+        self.data.p.iter().fold(BBox::empty(), |b, p| b.union_point(p))
+    }
+
+    fn area(&self) -> Float {
+        self.triangles().iter().map(|t| t.area()).sum()
+    }
+
+    fn can_intersect(&self) -> bool {
+        false
+    }
+
+    fn refine(&self, shapes: &mut Vec<Arc<Shape>>) {
+        for triangle in &self.triangles {
+            shapes.push(Arc::new(triangle.clone()));
+        }
+    }
+
+    fn reverse_orientation(&self) -> bool {
+        self.data.reverse_orientation
+    }
+
+    fn transform_swaps_handedness(&self) -> bool {
+        self.data.transform_swaps_handedness
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Triangle {
+    mesh: Arc<TriangleMeshData>,
+    v: usize,
+}
+
+impl Triangle {
+    fn vertices(&self) -> (Point3f, Point3f, Point3f) {
+        let p = &self.mesh.p;
+        let vi = &self.mesh.vertex_indices;
+        (p[vi[self.v]], p[vi[self.v + 1]], p[vi[self.v + 2]])
+    }
+
+    fn uvs(&self) -> [[Float; 2]; 3] {
+        match self.mesh.uvs {
+            Some(ref uvs) => {
+                let vi = &self.mesh.vertex_indices;
+                [uvs[vi[self.v]], uvs[vi[self.v + 1]], uvs[vi[self.v + 2]]]
+            }
+            None => [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]],
+        }
+    }
+
+    // Surface normal given by the winding of the vertices, flipped for reversed orientation
+    fn geometric_normal(&self) -> Normal {
+        let (p1, p2, p3) = self.vertices();
+        let n = Normal::from((p2 - p1).cross(p3 - p1).normalize());
+        if self.mesh.reverse_orientation ^ self.mesh.transform_swaps_handedness { -n } else { n }
+    }
+
+    // Solid angle subtended by the triangle at _p_ if it is to be sampled by direction. Both
+    // _sample_point_ and _pdf_ use this to choose between spherical and area sampling.
+    fn spherical_sample_area(&self, p: &Point3f) -> Option<Float> {
+        let (p1, p2, p3) = self.vertices();
+        let (a, b, c) = ((p1 - p).normalize(), (p2 - p).normalize(), (p3 - p).normalize());
+        let (alpha, beta, gamma) = spherical_triangle_angles(&a, &b, &c)?;
+        let area = alpha + beta + gamma - PI;
+        if (MIN_SPHERICAL_SAMPLE_AREA..=MAX_SPHERICAL_SAMPLE_AREA).contains(&area) {
+            Some(area)
+        } else {
+            None
+        }
+    }
+}
+
+impl Shape for Triangle {
+    fn intersect(&self, ray: &Ray) -> Option<(DifferentialGeometry<'_>, Float, Float)> {
+        let (p1, p2, p3) = self.vertices();
+
+        // Compute $\VEC{s}_1$
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+        let s1 = ray.d.cross(e2);
+        let divisor = s1.dot(e1);
+        if divisor == 0.0 {
+            return None;
+        }
+        let inv_divisor = 1.0 / divisor;
+
+        // Compute first barycentric coordinate
+        let s = ray.o - p1;
+        let b1 = s.dot(s1) * inv_divisor;
+        if !(0.0..=1.0).contains(&b1) {
+            return None;
+        }
+
+        // Compute second barycentric coordinate
+        let s2 = s.cross(e1);
+        let b2 = ray.d.dot(s2) * inv_divisor;
+        if b2 < 0.0 || b1 + b2 > 1.0 {
+            return None;
+        }
+
+        // Compute _t_ to intersection point
+        let t = e2.dot(s2) * inv_divisor;
+        if t < ray.mint || t > ray.maxt {
+            return None;
+        }
+
+        // Compute triangle partial derivatives
+        let uvs = self.uvs();
+        let du1 = uvs[0][0] - uvs[2][0];
+        let du2 = uvs[1][0] - uvs[2][0];
+        let dv1 = uvs[0][1] - uvs[2][1];
+        let dv2 = uvs[1][1] - uvs[2][1];
+        let dp1 = p1 - p3;
+        let dp2 = p2 - p3;
+        let determinant = du1 * dv2 - dv1 * du2;
+        let (dpdu, dpdv) = if determinant == 0.0 {
+            // Handle zero determinant for triangle partial derivative matrix
+            coordinate_system(&e2.cross(e1).normalize())
+        } else {
+            let invdet = 1.0 / determinant;
+            ((dv2 * dp1 - dv1 * dp2) * invdet, (-du2 * dp1 + du1 * dp2) * invdet)
+        };
+
+        // Interpolate $(u,v)$ triangle parametric coordinates
+        let b0 = 1.0 - b1 - b2;
+        let tu = b0 * uvs[0][0] + b1 * uvs[1][0] + b2 * uvs[2][0];
+        let tv = b0 * uvs[0][1] + b1 * uvs[1][1] + b2 * uvs[2][1];
+
+        // Fill in _DifferentialGeometry_ from triangle hit, keeping the normal consistent with the winding
+        let dn = Normal::new(0.0, 0.0, 0.0);
+        let mut dg = DifferentialGeometry::new(ray.point_at(t), dpdu, dpdv, dn, dn, tu, tv, self);
+        dg.nn = self.geometric_normal();
+
+        Some((dg, t, 1e-3 * t))
+    }
+
+    fn pdf(&self, p: &Point3f, wi: &Vector3f) -> Float {
+        match self.spherical_sample_area(p) {
+            Some(area) => {
+                let ray = Ray::new(*p, *wi, 0.0, INFINITY, 0.0);
+                if self.intersect(&ray).is_some() { 1.0 / area } else { 0.0 }
+            }
+            None => shape_pdf(self, p, wi),
+        }
+    }
+
+    fn get_object_to_world(&self) -> &Transform {
+        &self.mesh.object_to_world
+    }
+
+    fn object_bound(&self) -> BBox {
+        let world_to_object = self.mesh.object_to_world.invert();
+        let (p1, p2, p3) = self.vertices();
+        BBox::from_point(world_to_object.transform_point(p1))
+            .union_point(&world_to_object.transform_point(p2))
+            .union_point(&world_to_object.transform_point(p3))
+    }
+
+    fn world_bound(&self) -> BBox {
+        let (p1, p2, p3) = self.vertices();
+        BBox::from_point(p1).union_point(&p2).union_point(&p3)
+    }
+
+    fn area(&self) -> Float {
+        let (p1, p2, p3) = self.vertices();
+        0.5 * (p2 - p1).cross(p3 - p1).magnitude()
+    }
+
+    fn sample_point(&self, p: &Point3f, u1: Float, u2: Float) -> (Point3f, Normal) {
+        if self.spherical_sample_area(p).is_none() {
+            return self.sample(u1, u2);
+        }
+
+        // Sample direction inside the spherical triangle subtended at _p_
+        let (p1, p2, p3) = self.vertices();
+        let (a, b, c) = ((p1 - p).normalize(), (p2 - p).normalize(), (p3 - p).normalize());
+        let w = match uniform_sample_spherical_triangle(u1, u2, &a, &b, &c) {
+            Some((w, _)) => w,
+            None => return self.sample(u1, u2),
+        };
+
+        // Find the point on the triangle's plane in the sampled direction
+        let n = self.geometric_normal();
+        let t = (p1 - p).dot(n.v) / w.dot(n.v);
+        (p + t * w, n)
+    }
+
+    fn sample(&self, u1: Float, u2: Float) -> (Point3f, Normal) {
+        let (b1, b2) = uniform_sample_triangle(u1, u2);
+
+        // Compute triangle sample position
+        let (p1, p2, p3) = self.vertices();
+        let p = p1 + b1 * (p2 - p1) + b2 * (p3 - p1);
+
+        (p, self.geometric_normal())
+    }
+
+    fn reverse_orientation(&self) -> bool {
+        self.mesh.reverse_orientation
+    }
+
+    fn transform_swaps_handedness(&self) -> bool {
+        self.mesh.transform_swaps_handedness
+    }
+}
@@ -0,0 +1,34 @@
+use core::{
+    differential_geometry::DifferentialGeometry,
+    imageio::{RGBImage, ImageWrap, inverse_gamma_correct},
+    spectrum::Spectrum,
+    texture::{Texture, TextureMapping2D},
+};
+
+pub struct ImageTexture {
+    mapping: Box<TextureMapping2D>,
+    image: RGBImage,
+    wrap: ImageWrap,
+}
+
+impl ImageTexture {
+    // Set _srgb_ for 8-bit color images, which are decoded to linear values. Data such as normal
+    // maps and HDR images are used as stored.
+    pub fn new(mapping: Box<TextureMapping2D>, image: RGBImage, wrap: ImageWrap, srgb: bool) -> ImageTexture {
+        let image = if srgb {
+            image.map(|t| Spectrum::new(inverse_gamma_correct(t.r), inverse_gamma_correct(t.g), inverse_gamma_correct(t.b)))
+        } else {
+            image
+        };
+        ImageTexture { mapping, image, wrap }
+    }
+}
+
+impl Texture<Spectrum> for ImageTexture {
+    fn evaluate(&self, dg: &DifferentialGeometry) -> Spectrum {
+        let r = self.mapping.map(dg);
+
+        // Images are stored top row first while $t$ increases upwards
+        self.image.lookup(r.s, 1.0 - r.t, self.wrap)
+    }
+}
@@ -1,5 +1,7 @@
 mod checkerboard;
 mod constant;
+mod image;
 
 pub use self::checkerboard::{Checkerboard2DTexture, Checkerboard3DTexture, AAMethod};
-pub use self::constant::ConstantTexture;
\ No newline at end of file
+pub use self::constant::ConstantTexture;
+pub use self::image::ImageTexture;
\ No newline at end of file